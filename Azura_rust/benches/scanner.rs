@@ -0,0 +1,133 @@
+//! Scanner throughput on generated scripts of a few megabytes.
+//!
+//! To compare against another revision of the scanner, save a baseline there and compare to it:
+//!
+//! ```text
+//! git checkout <old> && cargo bench --bench scanner -- --save-baseline old
+//! git checkout <new> && cargo bench --bench scanner -- --baseline old
+//! ```
+//!
+//! With `--features c-scanner` the `scanToken` of the C implementation is measured on the same
+//! inputs. It only knows part of the language, so every input sticks to that part, apart from
+//! `block_comments` which it is not run on.
+use std::fmt::Write;
+
+use azura::scanner::Scanner;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Size every generated input grows to
+const SIZE: usize = 4 << 20;
+
+fn generate(mut line: impl FnMut(&mut String, usize)) -> String {
+    let mut source = String::with_capacity(SIZE + 256);
+    let mut idx = 0;
+    while source.len() < SIZE {
+        line(&mut source, idx);
+        idx += 1;
+    }
+    source
+}
+
+fn inputs() -> Vec<(&'static str, String)> {
+    let code = generate(|source, idx| {
+        let _ = writeln!(
+            source,
+            "have value_{idx} := {idx} * (offset + 3.25) - limit / 7;\n\
+             if value_{idx} >= 100 {{ info value_{idx}; }} else {{ total = total + 1; }}"
+        );
+    });
+    let comments = generate(|source, idx| {
+        let _ = writeln!(
+            source,
+            "// {idx}: the scanner has to skip over this line without looking at the words in it\n\
+             // and this one as well, which is long enough to make skipping the bulk of the work\n\
+             info {idx};"
+        );
+    });
+    let block_comments = generate(|source, idx| {
+        let _ = writeln!(
+            source,
+            "/* {idx}: a block comment spanning a few lines,\n\
+             \x20* with /* a nested comment */ and a `*` or `/` here and there\n\
+             \x20*/\n\
+             info {idx};"
+        );
+    });
+    let strings = generate(|source, idx| {
+        let _ = writeln!(
+            source,
+            "have text_{idx} := \"a string that is long enough for searching for the closing \
+             quote to matter, number {idx}, with some more words after it\";"
+        );
+    });
+    vec![
+        ("code", code),
+        ("comments", comments),
+        ("block_comments", block_comments),
+        ("strings", strings),
+    ]
+}
+
+fn scanner(c: &mut Criterion) {
+    let inputs = inputs();
+    let mut group = c.benchmark_group("scanner");
+    group.sample_size(20);
+    for (name, source) in &inputs {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::new("rust", name), source, |b, source| {
+            b.iter(|| Scanner::new(black_box(source)).count())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("rust_trivia", name),
+            source,
+            |b, source| b.iter(|| Scanner::new(black_box(source)).with_trivia().count()),
+        );
+        #[cfg(feature = "c-scanner")]
+        if *name != "block_comments" {
+            let source = std::ffi::CString::new(source.as_str()).unwrap();
+            group.bench_with_input(BenchmarkId::new("c", name), &source, |b, source| {
+                b.iter(|| c_scanner::count(black_box(source)))
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Bindings to `scanner.h` of the C implementation, which is built by `build.rs`
+#[cfg(feature = "c-scanner")]
+mod c_scanner {
+    use std::ffi::{c_char, c_int, CStr};
+
+    /// `TOKEN_EOF`, the last variant of `TokenType`
+    const TOKEN_EOF: c_int = 43;
+
+    #[repr(C)]
+    struct Token {
+        kind: c_int,
+        start: *const c_char,
+        length: c_int,
+        pos: c_int,
+        line: c_int,
+    }
+
+    extern "C" {
+        fn initScanner(source: *const c_char);
+        fn scanToken() -> Token;
+    }
+
+    /// Scans all of `source`, giving back the number of tokens including the last `TOKEN_EOF`
+    pub fn count(source: &CStr) -> usize {
+        let mut count = 1;
+        // SAFETY: `source` is NUL terminated and outlives the scanner, which is only used here
+        unsafe {
+            initScanner(source.as_ptr());
+            while scanToken().kind != TOKEN_EOF {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+criterion_group!(benches, scanner);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    // Only the benchmarks use the C scanner, so nothing is built without the feature
+    #[cfg(feature = "c-scanner")]
+    {
+        println!("cargo:rerun-if-changed=../src/scanner.c");
+        println!("cargo:rerun-if-changed=../src/scanner.h");
+        cc::Build::new()
+            .file("../src/scanner.c")
+            .include("../src")
+            .flag("-std=c17")
+            .opt_level(2)
+            .compile("azura_c_scanner");
+    }
+}
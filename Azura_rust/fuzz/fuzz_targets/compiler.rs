@@ -0,0 +1,13 @@
+//! Compiles and runs any input. There are no loops in the language yet, so every program ends.
+#![no_main]
+
+use std::io;
+
+use azura::vm::Vm;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    if let Err(error) = Vm::new(io::sink()).interpret(source) {
+        let _ = error.to_string();
+    }
+});
@@ -0,0 +1,29 @@
+//! Parses any input with the resolver, which keeps going after errors, and formats the input
+//! when it compiles, checking that formatting keeps the bytecode and gives back its own output.
+#![no_main]
+
+use azura::{
+    compiler::compile,
+    formatter::{format, FormatConfig},
+    resolver::resolve,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    let resolution = resolve(source);
+    for reference in &resolution.references {
+        if let Some(idx) = reference.declaration {
+            assert!(idx < resolution.declarations.len());
+        }
+    }
+
+    let Ok(original) = compile(source) else {
+        return;
+    };
+    let config = FormatConfig::default();
+    let formatted = format(source, &config).expect("the source compiles");
+    let chunk = compile(&formatted).expect("formatted code compiles");
+    assert_eq!(chunk.code, original.code, "{formatted}");
+    assert_eq!(chunk.constants, original.constants, "{formatted}");
+    assert_eq!(format(&formatted, &config).unwrap(), formatted);
+});
@@ -0,0 +1,33 @@
+//! Scans any input in every mode of the scanner, checking that trivia mode gives back the input.
+#![no_main]
+
+use azura::{
+    keywords::KEYWORDS,
+    scanner::{Scanner, StreamScanner},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Streams take any bytes, where invalid UTF-8 is an error rather than a panic
+    let split = data
+        .first()
+        .map_or(0, |&byte| usize::from(byte))
+        .min(data.len());
+    StreamScanner::from_chunks([&data[..split], &data[split..]]).for_each(drop);
+
+    let Ok(source) = std::str::from_utf8(data) else {
+        return;
+    };
+    let lexemes: String = Scanner::new(source)
+        .with_trivia()
+        .map(|result| match result {
+            Ok(token) => token.lexeme,
+            Err(error) => {
+                let _ = error.to_string();
+                error.lexeme
+            }
+        })
+        .collect();
+    assert_eq!(lexemes, source);
+    Scanner::new(source).with_keywords(&KEYWORDS).for_each(drop);
+});
@@ -0,0 +1,61 @@
+use crate::value::Value;
+
+/// A single bytecode instruction, with its operand if it takes one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpCode {
+    /// Pushes the constant at the given index
+    Constant(usize),
+    Nil,
+    True,
+    False,
+    Pop,
+    /// Pushes the local in the given stack slot
+    GetLocal(usize),
+    SetLocal(usize),
+    /// Global variables are looked up by the name stored in the constant at the given index
+    GetGlobal(usize),
+    SetGlobal(usize),
+    DefineGlobal(usize),
+    Equal,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    BitAnd,
+    BitOr,
+    BitXor,
+    LeftShift,
+    RightShift,
+    Not,
+    Negate,
+    Info,
+    Return,
+}
+
+/// Compiled bytecode, along with the line every instruction came from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Chunk {
+    pub code: Vec<OpCode>,
+    pub lines: Vec<usize>,
+    pub constants: Vec<Value>,
+}
+
+impl Chunk {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn write(&mut self, op: OpCode, line: usize) {
+        self.code.push(op);
+        self.lines.push(line);
+    }
+    /// Stores `value` in the constant table, returning its index
+    pub fn add_constant(&mut self, value: Value) -> usize {
+        self.constants.push(value);
+        self.constants.len() - 1
+    }
+}
@@ -0,0 +1,513 @@
+use std::rc::Rc;
+
+use crate::{
+    chunk::{Chunk, OpCode},
+    keywords::{Keyword, ScannerWithKeywords, KEYWORDS},
+    pragmas::{Lint, Pragma, Pragmas},
+    scanner::{Scanner, ScannerError, Span, Token, TokenKind},
+    value::Value,
+};
+
+pub use crate::error::{CompileError, ErrorLocation};
+
+/// Binding power of the binary operators, from loosest to tightest.
+///
+/// Bitwise operators bind tighter than comparisons, so `x & 1 == 0` tests the lowest bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Precedence {
+    None,
+    Assignment, // = += -= ...
+    Or,         // or
+    And,        // and
+    Equality,   // == !=
+    Comparison, // < > <= >=
+    BitOr,      // |
+    BitXor,     // ^
+    BitAnd,     // &
+    Shift,      // << >>
+    Term,       // + -
+    Factor,     // * / %
+    Unary,      // ! -
+    Call,       // . ()
+    Primary,
+}
+
+impl Precedence {
+    pub(crate) fn next(self) -> Self {
+        use Precedence::*;
+        match self {
+            None => Assignment,
+            Assignment => Or,
+            Or => And,
+            And => Equality,
+            Equality => Comparison,
+            Comparison => BitOr,
+            BitOr => BitXor,
+            BitXor => BitAnd,
+            BitAnd => Shift,
+            Shift => Term,
+            Term => Factor,
+            Factor => Unary,
+            Unary => Call,
+            Call | Primary => Primary,
+        }
+    }
+
+    /// The precedence of `kind` when used as a binary operator
+    pub(crate) fn of(kind: &TokenKind) -> Self {
+        use TokenKind::*;
+        match kind {
+            Equal | NotEqual => Precedence::Equality,
+            Greater | GreaterOrEqual | Less | LessOrEqual => Precedence::Comparison,
+            BitOr => Precedence::BitOr,
+            BitXor => Precedence::BitXor,
+            BitAnd => Precedence::BitAnd,
+            LeftShift | RightShift => Precedence::Shift,
+            Add | Sub => Precedence::Term,
+            Mul | Div | Rem => Precedence::Factor,
+            _ => Precedence::None,
+        }
+    }
+}
+
+/// The operation performed by a compound assignment like `+=`
+pub(crate) fn compound_operation(kind: &TokenKind) -> Option<OpCode> {
+    use TokenKind::*;
+    Some(match kind {
+        AddAssign => OpCode::Add,
+        SubAssign => OpCode::Subtract,
+        MulAssign => OpCode::Multiply,
+        DivAssign => OpCode::Divide,
+        RemAssign => OpCode::Remainder,
+        BitAndAssign => OpCode::BitAnd,
+        BitOrAssign => OpCode::BitOr,
+        BitXorAssign => OpCode::BitXor,
+        LeftShiftAssign => OpCode::LeftShift,
+        RightShiftAssign => OpCode::RightShift,
+        _ => return None,
+    })
+}
+
+struct Local<'a> {
+    name: &'a str,
+    /// `None` while the initializer of the variable is being compiled
+    depth: Option<usize>,
+    line: usize,
+    span: Span,
+    used: bool,
+}
+
+/// A single pass compiler, turning tokens straight into bytecode
+struct Compiler<'a> {
+    tokens: ScannerWithKeywords<'a, &'static phf::Map<&'static str, Keyword>>,
+    current: Token<'a>,
+    previous: Token<'a>,
+    errors: Vec<CompileError>,
+    /// Set after an error, until the compiler gets back to the start of a statement
+    panic_mode: bool,
+    locals: Vec<Local<'a>>,
+    scope_depth: usize,
+    chunk: Chunk,
+    pragmas: Pragmas,
+    /// Set once the first token that is not a pragma was read, after which pragmas are errors
+    code_started: bool,
+}
+
+/// Compiles `source` into a chunk that can be run by the [`Vm`](crate::vm::Vm)
+pub fn compile(source: &str) -> Result<Chunk, Vec<CompileError>> {
+    let placeholder = Token {
+        kind: TokenKind::Eof,
+        lexeme: "",
+        line: 1,
+        pos: 1,
+        span: Span::default(),
+    };
+    let mut compiler = Compiler {
+        tokens: Scanner::new(source).with_keywords(&KEYWORDS),
+        current: placeholder.clone(),
+        previous: placeholder,
+        errors: Vec::new(),
+        panic_mode: false,
+        locals: Vec::new(),
+        scope_depth: 0,
+        chunk: Chunk::new(),
+        pragmas: Pragmas::default(),
+        code_started: false,
+    };
+    compiler.advance();
+    while !compiler.matches(&TokenKind::Eof) {
+        compiler.declaration();
+    }
+    compiler.emit(OpCode::Return);
+    if compiler.errors.is_empty() {
+        Ok(compiler.chunk)
+    } else {
+        Err(compiler.errors)
+    }
+}
+
+impl<'a> Compiler<'a> {
+    fn advance(&mut self) {
+        self.previous = self.current.clone();
+        // The scanner stops after the end of file token, which then stays the current one
+        while let Some(token) = self.tokens.next() {
+            match token {
+                Ok(Token {
+                    kind: TokenKind::DocComment(..),
+                    ..
+                }) => continue,
+                Ok(
+                    token @ Token {
+                        kind: TokenKind::Pragma(text),
+                        ..
+                    },
+                ) => self.pragma(token, text),
+                Ok(token) => {
+                    self.code_started = true;
+                    self.current = token;
+                    break;
+                }
+                Err(error) => self.scanner_error(&error),
+            }
+        }
+    }
+    fn pragma(&mut self, token: Token<'a>, text: &str) {
+        if self.code_started {
+            self.error_at(token, "Pragmas must come before any code!");
+            return;
+        }
+        match Pragma::parse(text) {
+            Ok(pragma) => self.pragmas.apply(pragma),
+            Err(error) => self.error_at(token, &error.to_string()),
+        }
+    }
+    fn check(&self, kind: &TokenKind) -> bool {
+        self.current.kind == *kind
+    }
+    fn matches(&mut self, kind: &TokenKind) -> bool {
+        if !self.check(kind) {
+            return false;
+        }
+        self.advance();
+        true
+    }
+    fn consume(&mut self, kind: &TokenKind, message: &str) {
+        if self.check(kind) {
+            self.advance();
+            return;
+        }
+        self.error_at_current(message);
+    }
+
+    fn error(&mut self, message: &str) {
+        self.error_at(self.previous.clone(), message);
+    }
+    fn error_at_current(&mut self, message: &str) {
+        self.error_at(self.current.clone(), message);
+    }
+    fn error_at(&mut self, token: Token, message: &str) {
+        if self.panic_mode {
+            return;
+        }
+        self.panic_mode = true;
+        let location = match token.kind {
+            TokenKind::Eof => ErrorLocation::End,
+            _ => ErrorLocation::Token(token.lexeme.to_owned()),
+        };
+        self.errors.push(CompileError {
+            line: token.line,
+            location,
+            message: message.to_owned(),
+            span: token.span,
+        });
+    }
+    fn scanner_error(&mut self, error: &ScannerError) {
+        if self.panic_mode {
+            return;
+        }
+        self.panic_mode = true;
+        self.errors.push(CompileError::from_scanner(error));
+    }
+
+    fn emit(&mut self, op: OpCode) {
+        self.chunk.write(op, self.previous.line);
+    }
+    fn emit_constant(&mut self, value: Value) {
+        let constant = self.chunk.add_constant(value);
+        self.emit(OpCode::Constant(constant));
+    }
+    fn identifier_constant(&mut self, name: &str) -> usize {
+        self.chunk.add_constant(Value::Str(Rc::from(name)))
+    }
+
+    fn begin_scope(&mut self) {
+        self.scope_depth += 1;
+    }
+    fn end_scope(&mut self) {
+        self.scope_depth -= 1;
+        while matches!(self.locals.last(), Some(local) if local.depth > Some(self.scope_depth)) {
+            self.emit(OpCode::Pop);
+            let Some(local) = self.locals.pop() else {
+                break;
+            };
+            if !local.used && !local.name.starts_with('_') && self.pragmas.denies(Lint::Unused) {
+                // Reported even while recovering from another error, as it belongs to a
+                // different statement
+                self.errors.push(CompileError {
+                    line: local.line,
+                    location: ErrorLocation::Token(local.name.to_owned()),
+                    message: format!("Unused local variable '{}'!", local.name),
+                    span: local.span,
+                });
+            }
+        }
+    }
+
+    fn declaration(&mut self) {
+        if self.matches(&TokenKind::Keyword(Keyword::Have)) {
+            self.var_declaration();
+        } else {
+            self.statement();
+        }
+        if self.panic_mode {
+            self.synchronize();
+        }
+    }
+    fn var_declaration(&mut self) {
+        let global = self.parse_variable("Expected a variable name!");
+
+        if self.matches(&TokenKind::Walrus) {
+            self.expression();
+        } else {
+            self.emit(OpCode::Nil);
+        }
+
+        if self.matches(&TokenKind::Reassignment) {
+            self.error(
+                "\nFor assining a variable use the operation of ':='.\nFor example \
+                 'have add := 45.2 + 2'. Happy coding!",
+            );
+        }
+        if self.matches(&TokenKind::Semicolon) {
+            self.error(
+                "\nWoops! you used a semicolon at the end of the variable declaration!\n \
+                 You only need to use a ';' at the end of an info statement. Happy coding!",
+            );
+        }
+
+        self.define_variable(global);
+    }
+    /// Declares the variable named by the next token, returning the constant holding its name
+    /// if it is a global
+    fn parse_variable(&mut self, message: &str) -> Option<usize> {
+        let TokenKind::Ident(name) = self.current.kind else {
+            self.error_at_current(message);
+            return None;
+        };
+        self.advance();
+
+        if self.scope_depth == 0 {
+            return Some(self.identifier_constant(name));
+        }
+        let shadows = self
+            .locals
+            .iter()
+            .rev()
+            .take_while(|local| local.depth.is_none_or(|depth| depth >= self.scope_depth))
+            .any(|local| local.name == name);
+        if shadows {
+            self.error("Already a variable with this name declared in the scope!");
+        }
+        self.locals.push(Local {
+            name,
+            depth: None,
+            line: self.previous.line,
+            span: self.previous.span,
+            used: false,
+        });
+        None
+    }
+    fn define_variable(&mut self, global: Option<usize>) {
+        match global {
+            Some(global) => self.emit(OpCode::DefineGlobal(global)),
+            None => {
+                if let Some(local) = self.locals.last_mut() {
+                    local.depth = Some(self.scope_depth);
+                }
+            }
+        }
+    }
+
+    fn statement(&mut self) {
+        if self.matches(&TokenKind::Keyword(Keyword::Info)) {
+            self.expression();
+            self.consume(
+                &TokenKind::Semicolon,
+                "Expected ';' after value! \nTry something like this 'info 1 + 1;' happy coding!",
+            );
+            self.emit(OpCode::Info);
+        } else if self.matches(&TokenKind::OBrace) {
+            self.begin_scope();
+            self.block();
+            self.end_scope();
+        } else {
+            self.expression();
+            self.emit(OpCode::Pop);
+        }
+    }
+    fn block(&mut self) {
+        while !self.check(&TokenKind::CBrace) && !self.check(&TokenKind::Eof) {
+            self.declaration();
+        }
+        self.consume(&TokenKind::CBrace, "Expect '}' after block.");
+    }
+    fn synchronize(&mut self) {
+        self.panic_mode = false;
+        while !self.check(&TokenKind::Eof) {
+            if self.previous.kind == TokenKind::Semicolon {
+                return;
+            }
+            if let TokenKind::Keyword(
+                Keyword::Class
+                | Keyword::Func
+                | Keyword::Have
+                | Keyword::For
+                | Keyword::If
+                | Keyword::While
+                | Keyword::Info
+                | Keyword::Return,
+            ) = self.current.kind
+            {
+                return;
+            }
+            self.advance();
+        }
+    }
+
+    fn expression(&mut self) {
+        self.parse_precedence(Precedence::Assignment);
+    }
+    fn parse_precedence(&mut self, precedence: Precedence) {
+        self.advance();
+        let can_assign = precedence <= Precedence::Assignment;
+        if !self.prefix(can_assign) {
+            self.error("Expect expression.");
+            return;
+        }
+
+        while precedence <= Precedence::of(&self.current.kind) {
+            self.advance();
+            self.binary();
+        }
+
+        let assignment = self.check(&TokenKind::Reassignment)
+            || compound_operation(&self.current.kind).is_some();
+        if can_assign && assignment {
+            self.advance();
+            self.error("Invalid assignment target!");
+        }
+    }
+    /// Compiles the expression starting with the previous token, `false` if no expression can
+    /// start with it
+    fn prefix(&mut self, can_assign: bool) -> bool {
+        match self.previous.kind {
+            TokenKind::OPar => {
+                self.expression();
+                self.consume(&TokenKind::CPar, "Expect ')' after expression!");
+            }
+            TokenKind::Sub | TokenKind::Bang => self.unary(),
+            TokenKind::Ident(name) => self.named_variable(name, can_assign),
+            TokenKind::Str(string) => self.emit_constant(Value::Str(Rc::from(&*string.value()))),
+            TokenKind::Char(ch) => self.emit_constant(Value::Char(ch)),
+            TokenKind::Byte(byte) => self.emit_constant(Value::Byte(byte)),
+            TokenKind::ByteStr(string) => {
+                self.emit_constant(Value::Bytes(Rc::from(&*string.bytes())))
+            }
+            TokenKind::Integer(int) => self.emit_constant(Value::Int(int)),
+            TokenKind::Float(float) => self.emit_constant(Value::Float(float)),
+            TokenKind::BigInteger(literal) => match Value::from_big_literal(literal) {
+                Ok(value) => self.emit_constant(value),
+                Err(_) => self.error("Integer literal is too large to be represented."),
+            },
+            TokenKind::Keyword(Keyword::True) => self.emit(OpCode::True),
+            TokenKind::Keyword(Keyword::False) => self.emit(OpCode::False),
+            TokenKind::Keyword(Keyword::Nil) => self.emit(OpCode::Nil),
+            _ => return false,
+        }
+        true
+    }
+    fn unary(&mut self) {
+        let operator = self.previous.kind.clone();
+        self.parse_precedence(Precedence::Unary);
+        match operator {
+            TokenKind::Bang => self.emit(OpCode::Not),
+            TokenKind::Sub => self.emit(OpCode::Negate),
+            _ => unreachable!("not a unary operator"),
+        }
+    }
+    fn binary(&mut self) {
+        use TokenKind::*;
+        let operator = self.previous.kind.clone();
+        self.parse_precedence(Precedence::of(&operator).next());
+        let op = match operator {
+            NotEqual => {
+                self.emit(OpCode::Equal);
+                OpCode::Not
+            }
+            Equal => OpCode::Equal,
+            Greater => OpCode::Greater,
+            GreaterOrEqual => OpCode::GreaterOrEqual,
+            Less => OpCode::Less,
+            LessOrEqual => OpCode::LessOrEqual,
+            Add => OpCode::Add,
+            Sub => OpCode::Subtract,
+            Mul => OpCode::Multiply,
+            Div => OpCode::Divide,
+            Rem => OpCode::Remainder,
+            BitAnd => OpCode::BitAnd,
+            BitOr => OpCode::BitOr,
+            BitXor => OpCode::BitXor,
+            LeftShift => OpCode::LeftShift,
+            RightShift => OpCode::RightShift,
+            _ => unreachable!("not a binary operator"),
+        };
+        self.emit(op);
+    }
+    fn named_variable(&mut self, name: &'a str, can_assign: bool) {
+        let (get, set) = match self.resolve_local(name) {
+            Some(slot) => (OpCode::GetLocal(slot), OpCode::SetLocal(slot)),
+            None => {
+                let constant = self.identifier_constant(name);
+                (OpCode::GetGlobal(constant), OpCode::SetGlobal(constant))
+            }
+        };
+
+        if can_assign && self.matches(&TokenKind::Reassignment) {
+            self.expression();
+            self.emit(set);
+        } else if let Some(operation) =
+            compound_operation(&self.current.kind).filter(|_| can_assign)
+        {
+            self.advance();
+            self.emit(get);
+            self.expression();
+            self.emit(operation);
+            self.emit(set);
+        } else {
+            self.emit(get);
+        }
+    }
+    fn resolve_local(&mut self, name: &str) -> Option<usize> {
+        let (slot, local) = self
+            .locals
+            .iter_mut()
+            .enumerate()
+            .rev()
+            .find(|(_, local)| local.name == name)?;
+        local.used = true;
+        if local.depth.is_none() {
+            self.error("Can't read local variable in its own initializer!");
+        }
+        Some(slot)
+    }
+}
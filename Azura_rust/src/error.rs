@@ -1,10 +1,10 @@
 use std::borrow::Cow;
 
-use crate::scanner::TokenKind;
+use crate::scanner::{Span, TokenKind};
 
 mod format;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ScannerErrorKind<'a> {
     Unmatched {
         token: Option<TokenKind<'a>>,
@@ -13,31 +13,218 @@ pub enum ScannerErrorKind<'a> {
         token: Option<TokenKind<'a>>,
     },
     IncorrectLiteral {
-        parse_error: Option<Box<dyn std::error::Error + Send>>,
-    },
-}
-
-impl<'a> PartialEq for ScannerErrorKind<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Unmatched { token }, Self::Unmatched { token: token2 }) if token == token2 => {
-                true
-            }
-            (Self::IncompleteToken { token }, Self::IncompleteToken { token: token2 })
-                if token == token2 =>
-            {
-                true
-            }
-            _ => false,
-        }
-    }
+        error: LiteralError,
+    },
+    UnterminatedBlockComment,
+    UnexpectedCharacter {
+        character: char,
+    },
+    /// A `\` in a literal that is not followed by a known escape, i.e. `\q` or `\u{110000}`
+    InvalidEscape {
+        sequence: &'a str,
+    },
+}
+
+/// The reason a number, character or byte literal could not be turned into a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralError {
+    /// The literal is too large to be represented, i.e. an infinite float
+    Overflow,
+    /// A character that is not a digit in the literal's radix, i.e. `9` in `0o19`
+    InvalidDigit { digit: char, radix: u32 },
+    /// A radix prefix without any digits following it, i.e. `0x`
+    EmptyDigits,
+    /// A float written with a radix prefix, i.e. `0b1.1`
+    FloatWithRadix { radix: u32 },
+    /// An exponent without digits or with a misplaced sign, i.e. `1.0e`
+    MalformedExponent,
+    /// A character literal without a character in it, i.e. `''`
+    EmptyChar,
+    /// A character literal holding more than one codepoint, i.e. `'ab'` or `'é'` written with a
+    /// combining accent
+    MultipleCodepoints { count: usize },
+    /// A character that is not ASCII in a byte or byte string literal, i.e. `b'é'`
+    NonAscii { character: char },
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ScannerError<'a> {
     pub kind: ScannerErrorKind<'a>,
     pub line: usize,
+    /// Column of the character the error points at, starting at 1
     pub pos: usize,
     pub message: Option<Cow<'a, str>>,
     pub context: Option<&'a str>,
+    /// All of the source text that was skipped over because of this error
+    pub lexeme: &'a str,
+    /// Location of `lexeme`, which also contains `pos`
+    pub span: Span,
+}
+
+/// A [`ScannerError`] that owns its text, so it can outlive the source it was scanned from
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedScannerError {
+    /// The kind with its text left empty, which is stored in `text` instead
+    kind: ScannerErrorKind<'static>,
+    text: String,
+    pub line: usize,
+    pub pos: usize,
+    pub message: Option<String>,
+    pub context: Option<String>,
+    pub lexeme: String,
+    pub span: Span,
+}
+
+impl<'a> ScannerErrorKind<'a> {
+    /// Replaces the text the error borrows from the source, keeping everything else as it is
+    pub fn map_text<'b>(self, f: impl FnOnce(&'a str) -> &'b str) -> ScannerErrorKind<'b> {
+        use ScannerErrorKind::*;
+        match self {
+            Unmatched { token } => Unmatched {
+                token: token.map(|token| token.map_text(f)),
+            },
+            IncompleteToken { token } => IncompleteToken {
+                token: token.map(|token| token.map_text(f)),
+            },
+            IncorrectLiteral { error } => IncorrectLiteral { error },
+            UnterminatedBlockComment => UnterminatedBlockComment,
+            UnexpectedCharacter { character } => UnexpectedCharacter { character },
+            InvalidEscape { sequence } => InvalidEscape {
+                sequence: f(sequence),
+            },
+        }
+    }
+}
+
+impl OwnedScannerError {
+    /// Borrows the error, to be used wherever a [`ScannerError`] is expected
+    pub fn as_error(&self) -> ScannerError<'_> {
+        ScannerError {
+            kind: self.kind.clone().map_text(|_| &self.text),
+            line: self.line,
+            pos: self.pos,
+            message: self.message.as_deref().map(Cow::Borrowed),
+            context: self.context.as_deref(),
+            lexeme: &self.lexeme,
+            span: self.span,
+        }
+    }
+}
+
+impl<'a> ScannerError<'a> {
+    pub fn into_owned(self) -> OwnedScannerError {
+        let mut text = String::new();
+        OwnedScannerError {
+            kind: self.kind.map_text(|borrowed| {
+                text = borrowed.to_owned();
+                ""
+            }),
+            text,
+            line: self.line,
+            pos: self.pos,
+            message: self.message.map(Cow::into_owned),
+            context: self.context.map(str::to_owned),
+            lexeme: self.lexeme.to_owned(),
+            span: self.span,
+        }
+    }
+}
+
+/// What a [`CompileError`] points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorLocation {
+    /// A token, given by its lexeme
+    Token(String),
+    /// The end of input, given by the [`TokenKind::Eof`] token
+    End,
+    /// An error from the scanner, which has no complete token to point at
+    Scanner,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    pub line: usize,
+    pub location: ErrorLocation,
+    pub message: String,
+    /// Location of the token or scanner error in the source, empty at the end of input
+    pub span: Span,
 }
+
+impl CompileError {
+    pub fn from_scanner(error: &ScannerError) -> Self {
+        let message = error.message.as_deref().unwrap_or("Unexpected input");
+        CompileError {
+            line: error.line,
+            location: ErrorLocation::Scanner,
+            message: match &error.kind {
+                ScannerErrorKind::IncorrectLiteral { error } => format!("{message}: {error}"),
+                _ => message.to_owned(),
+            },
+            span: error.span,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeError {
+    pub message: String,
+    /// Line of the instruction that failed
+    pub line: usize,
+}
+
+/// A `#` pragma that could not be understood
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PragmaError {
+    Unknown {
+        name: String,
+    },
+    UnknownEdition {
+        edition: String,
+    },
+    UnknownLint {
+        lint: String,
+    },
+    /// A known pragma written the wrong way, i.e. `#allow unused`
+    Malformed {
+        name: &'static str,
+        expected: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpretError {
+    /// Every error found while compiling, the program is not run if there are any
+    Compile(Vec<CompileError>),
+    Runtime(RuntimeError),
+}
+
+pub(crate) fn radix_name(radix: u32) -> &'static str {
+    match radix {
+        2 => "binary",
+        8 => "octal",
+        10 => "decimal",
+        16 => "hexadecimal",
+        _ => "numeric",
+    }
+}
+
+impl std::error::Error for LiteralError {}
+
+impl<'a> std::error::Error for ScannerError<'a> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ScannerErrorKind::IncorrectLiteral { error } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for OwnedScannerError {}
+
+impl std::error::Error for PragmaError {}
+
+impl std::error::Error for CompileError {}
+
+impl std::error::Error for RuntimeError {}
+
+impl std::error::Error for InterpretError {}
@@ -2,7 +2,11 @@ use std::fmt::Display;
 
 use owo_colors::{OwoColorize, Stream::Stderr, Style};
 
-use super::{ScannerError, ScannerErrorKind};
+use super::{
+    radix_name, CompileError, ErrorLocation, InterpretError, LiteralError, OwnedScannerError,
+    PragmaError, RuntimeError, ScannerError, ScannerErrorKind,
+};
+use crate::keywords::Edition;
 
 const MAX_CONTEXT_LINES: usize = 4;
 
@@ -15,26 +19,17 @@ impl<'a> Display for ScannerError<'a> {
             pos,
             message,
             context,
+            ..
         } = self;
-        // let show_pos = match kind {
-        //     EndOfInput => false,
-        //     _ => true,
-        // };
-        let show_pos = true;
-        if show_pos {
-            write!(
-                f,
-                "{} at {}:{}\n{}",
-                "Error".if_supports_color(Stderr, |text| text.blue()),
-                line.if_supports_color(Stderr, |text| text.bright_red()),
-                pos.if_supports_color(Stderr, |text| text.red()),
-                message
-                    .as_ref()
-                    .map(|x| x.as_ref())
-                    .unwrap_or_default()
-                    .if_supports_color(Stderr, |text| text.italic())
-            )?
-        };
+        let message: &str = message.as_ref().map(|x| x.as_ref()).unwrap_or_default();
+        let message = message.if_supports_color(Stderr, |text| text.italic());
+        write!(
+            f,
+            "{} at {}:{}\n{message}",
+            "Error".if_supports_color(Stderr, |text| text.blue()),
+            line.if_supports_color(Stderr, |text| text.bright_red()),
+            pos.if_supports_color(Stderr, |text| text.red()),
+        )?;
         let end_text = match kind {
             Unmatched { token } => {
                 f.write_str("\n")?;
@@ -63,19 +58,34 @@ impl<'a> Display for ScannerError<'a> {
                 };
                 ""
             }
-            IncorrectLiteral { parse_error: error } => {
-                if let Some(error) = error {
-                    write!(
-                        f,
-                        "\n{:?}",
-                        error.if_supports_color(Stderr, |text| {
-                            let style = Style::new().red().bold();
-                            text.style(style)
-                        })
-                    )?
-                }
+            IncorrectLiteral { error } => {
+                write!(
+                    f,
+                    "\n{}",
+                    error.if_supports_color(Stderr, |text| {
+                        let style = Style::new().red().bold();
+                        text.style(style)
+                    })
+                )?;
                 "\nIncorrect literal"
             }
+            UnterminatedBlockComment => "\nUnterminated block comment",
+            InvalidEscape { sequence } => {
+                write!(
+                    f,
+                    "\nInvalid escape sequence: `{}`",
+                    sequence.if_supports_color(Stderr, |text| text.blue())
+                )?;
+                "\nIncorrect literal"
+            }
+            UnexpectedCharacter { character } => {
+                write!(
+                    f,
+                    "\nUnexpected character: {:?}",
+                    character.if_supports_color(Stderr, |text| text.blue())
+                )?;
+                ""
+            }
         };
         f.write_str(end_text)?;
         if let Some(context) = context.as_deref() {
@@ -118,3 +128,93 @@ impl<'a> Display for ScannerError<'a> {
         Ok(())
     }
 }
+
+impl Display for LiteralError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use LiteralError::*;
+        match self {
+            Overflow => f.write_str("number is too large to be represented"),
+            InvalidDigit { digit, radix } => {
+                let name = radix_name(*radix);
+                let article = if name.starts_with('o') { "an" } else { "a" };
+                write!(
+                    f,
+                    "digit `{digit}` is not valid in {article} {name} literal"
+                )
+            }
+            EmptyDigits => f.write_str("no digits found after the literal prefix"),
+            FloatWithRadix { radix } => write!(
+                f,
+                "{} literals can not have a fractional part",
+                radix_name(*radix)
+            ),
+            MalformedExponent => f.write_str("expected at least one digit in the exponent"),
+            EmptyChar => f.write_str("character literals must contain exactly one character"),
+            MultipleCodepoints { count } => write!(
+                f,
+                "character literals must contain exactly one codepoint, found {count}"
+            ),
+            NonAscii { character } => write!(
+                f,
+                "{character:?} is not ASCII, use a `\\x` escape to write its bytes"
+            ),
+        }
+    }
+}
+
+impl Display for OwnedScannerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_error().fmt(f)
+    }
+}
+
+/// Matches the format of the C implementation, i.e. `[line 1] Error at 'x': message`
+impl Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[line {}] Error", self.line)?;
+        match &self.location {
+            ErrorLocation::Token(lexeme) => write!(f, " at '{lexeme}'")?,
+            ErrorLocation::End => f.write_str(" at end")?,
+            ErrorLocation::Scanner => (),
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl Display for PragmaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PragmaError::Unknown { name } => write!(f, "Unknown pragma '{name}'."),
+            PragmaError::UnknownEdition { edition } => write!(
+                f,
+                "Unknown edition '{edition}', expected a number from 1 to {}.",
+                Edition::LATEST
+            ),
+            PragmaError::UnknownLint { lint } => write!(f, "Unknown lint '{lint}'."),
+            PragmaError::Malformed { name, expected } => {
+                write!(f, "Malformed '#{name}' pragma, expected {expected}.")
+            }
+        }
+    }
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n[line {}] in script", self.message, self.line)
+    }
+}
+
+impl Display for InterpretError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterpretError::Compile(errors) => {
+                let mut errors = errors.iter();
+                if let Some(first) = errors.next() {
+                    write!(f, "{first}")?;
+                }
+                errors.try_for_each(|error| write!(f, "\n{error}"))
+            }
+            InterpretError::Runtime(error) => write!(f, "{error}"),
+        }
+    }
+}
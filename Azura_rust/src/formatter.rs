@@ -0,0 +1,448 @@
+//! An opinionated formatter, as used by `azura fmt`.
+//!
+//! Every statement goes on its own line, blocks are indented with their braces placed like in
+//! `test.az`, binary operators, `:=` and assignments get a space on either side and at most one
+//! blank line is kept between statements. Expressions that do not fit into the line width are
+//! broken before their operators with the loosest precedence.
+//!
+//! Comments are kept where they are, either on their own line or after a statement. A statement
+//! with a comment in the middle of it is left as it is written, apart from its indentation.
+use std::mem;
+
+use crate::{
+    compiler::{self, compound_operation, CompileError, Precedence},
+    keywords::{Keyword, KEYWORDS},
+    scanner::{Scanner, Token, TokenKind},
+};
+
+/// How [`format()`] lays out code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatConfig {
+    /// The number of characters a line should not go over, which is only exceeded by single
+    /// tokens and comments that do not fit on their own
+    pub max_width: usize,
+    /// The number of spaces for each level of indentation
+    pub indent_width: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            max_width: 100,
+            indent_width: 4,
+        }
+    }
+}
+
+/// Formats `source`, which is only done if it compiles, giving back the errors otherwise
+pub fn format(source: &str, config: &FormatConfig) -> Result<String, Vec<CompileError>> {
+    compiler::compile(source)?;
+    let tokens = tokens(source);
+    let mut parser = Parser {
+        tokens: &tokens,
+        current: 0,
+    };
+    // The compiler accepted the source, so this only fails if the grammars do not agree
+    let Some(items) = parser.declarations() else {
+        return Ok(source.to_owned());
+    };
+    let mut printer = Printer {
+        config,
+        source,
+        tokens: &tokens,
+        out: String::with_capacity(source.len()),
+        indent: 0,
+        line_started: false,
+    };
+    printer.items(&items, tokens.len() - 1);
+    let mut out = printer.out;
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// A comment, which is printed as written
+struct Comment<'a> {
+    text: &'a str,
+    /// The number of line breaks between the comment and what comes before it
+    newlines: usize,
+}
+
+/// A token that is not trivia, with the comments before it
+struct Tok<'a> {
+    token: Token<'a>,
+    comments: Vec<Comment<'a>>,
+    /// The number of line breaks between the token and the comment or token before it
+    newlines: usize,
+}
+
+fn tokens(source: &str) -> Vec<Tok<'_>> {
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let mut newlines = 0;
+    for token in Scanner::new(source)
+        .with_trivia()
+        .with_keywords(&KEYWORDS)
+        .flatten()
+    {
+        match token.kind {
+            TokenKind::Whitespace(_) => (),
+            TokenKind::Newline => newlines += 1,
+            TokenKind::LineComment(_)
+            | TokenKind::BlockComment(_)
+            | TokenKind::DocComment(..)
+            | TokenKind::Shebang(_) => comments.push(Comment {
+                text: token.lexeme,
+                newlines: mem::take(&mut newlines),
+            }),
+            _ => tokens.push(Tok {
+                token,
+                comments: mem::take(&mut comments),
+                newlines: mem::take(&mut newlines),
+            }),
+        }
+    }
+    tokens
+}
+
+enum Expr<'a> {
+    /// A literal or variable, as written
+    Leaf(&'a str),
+    Group(Box<Expr<'a>>),
+    Unary(&'a str, Box<Expr<'a>>),
+    Binary(Box<Expr<'a>>, &'a str, Precedence, Box<Expr<'a>>),
+    Assign(&'a str, &'a str, Box<Expr<'a>>),
+}
+
+enum Stmt<'a> {
+    Pragma(&'a str),
+    Have(&'a str, Option<Expr<'a>>),
+    Info(Expr<'a>),
+    Block(Vec<Item<'a>>),
+    Expr(Expr<'a>),
+}
+
+/// A statement with the indices of its first and last token
+struct Item<'a> {
+    stmt: Stmt<'a>,
+    first: usize,
+    last: usize,
+}
+
+/// Parses the same grammar as the compiler, keeping the tokens instead of emitting bytecode
+struct Parser<'t, 'a> {
+    tokens: &'t [Tok<'a>],
+    current: usize,
+}
+
+impl<'a> Parser<'_, 'a> {
+    fn kind(&self) -> &TokenKind<'a> {
+        &self.tokens[self.current].token.kind
+    }
+    fn advance(&mut self) -> &Token<'a> {
+        let token = &self.tokens[self.current].token;
+        if token.kind != TokenKind::Eof {
+            self.current += 1;
+        }
+        token
+    }
+    fn consume(&mut self, kind: &TokenKind) -> Option<()> {
+        (self.kind() == kind).then(|| {
+            self.advance();
+        })
+    }
+
+    fn declarations(&mut self) -> Option<Vec<Item<'a>>> {
+        let mut items = Vec::new();
+        while !matches!(self.kind(), TokenKind::Eof | TokenKind::CBrace) {
+            items.push(self.declaration()?);
+        }
+        Some(items)
+    }
+    fn declaration(&mut self) -> Option<Item<'a>> {
+        let first = self.current;
+        let stmt = match self.kind() {
+            TokenKind::Pragma(_) => Stmt::Pragma(self.advance().lexeme),
+            TokenKind::Keyword(Keyword::Have) => {
+                self.advance();
+                let TokenKind::Ident(name) = self.advance().kind else {
+                    return None;
+                };
+                let init = match self.consume(&TokenKind::Walrus) {
+                    Some(()) => Some(self.expression()?),
+                    None => None,
+                };
+                Stmt::Have(name, init)
+            }
+            TokenKind::Keyword(Keyword::Info) => {
+                self.advance();
+                let expr = self.expression()?;
+                self.consume(&TokenKind::Semicolon)?;
+                Stmt::Info(expr)
+            }
+            TokenKind::OBrace => {
+                self.advance();
+                let items = self.declarations()?;
+                self.consume(&TokenKind::CBrace)?;
+                Stmt::Block(items)
+            }
+            _ => Stmt::Expr(self.expression()?),
+        };
+        Some(Item {
+            stmt,
+            first,
+            last: self.current - 1,
+        })
+    }
+
+    fn expression(&mut self) -> Option<Expr<'a>> {
+        self.parse_precedence(Precedence::Assignment)
+    }
+    fn parse_precedence(&mut self, precedence: Precedence) -> Option<Expr<'a>> {
+        let can_assign = precedence <= Precedence::Assignment;
+        let token = self.advance().clone();
+        let mut expr = match token.kind {
+            TokenKind::OPar => {
+                let expr = self.expression()?;
+                self.consume(&TokenKind::CPar)?;
+                Expr::Group(Box::new(expr))
+            }
+            TokenKind::Sub | TokenKind::Bang => Expr::Unary(
+                token.lexeme,
+                Box::new(self.parse_precedence(Precedence::Unary)?),
+            ),
+            TokenKind::Ident(name) if can_assign && is_assignment(self.kind()) => {
+                let operator = self.advance().lexeme;
+                Expr::Assign(name, operator, Box::new(self.expression()?))
+            }
+            TokenKind::Ident(_)
+            | TokenKind::Str(_)
+            | TokenKind::Char(_)
+            | TokenKind::Byte(_)
+            | TokenKind::ByteStr(_)
+            | TokenKind::Integer(_)
+            | TokenKind::Float(_)
+            | TokenKind::BigInteger(_)
+            | TokenKind::Keyword(Keyword::True | Keyword::False | Keyword::Nil) => {
+                Expr::Leaf(token.lexeme)
+            }
+            _ => return None,
+        };
+        while precedence <= Precedence::of(self.kind()) {
+            let operator = Precedence::of(self.kind());
+            let lexeme = self.advance().lexeme;
+            let right = self.parse_precedence(operator.next())?;
+            expr = Expr::Binary(Box::new(expr), lexeme, operator, Box::new(right));
+        }
+        Some(expr)
+    }
+}
+
+/// `- -x` is written with a space, so it is not mistaken for a decrement
+fn double_negation(operator: &str, operand: &Expr) -> bool {
+    operator == "-" && matches!(operand, Expr::Unary("-", _))
+}
+
+fn is_assignment(kind: &TokenKind) -> bool {
+    *kind == TokenKind::Reassignment || compound_operation(kind).is_some()
+}
+
+impl Expr<'_> {
+    /// The expression written on a single line
+    fn flat(&self) -> String {
+        match self {
+            Expr::Leaf(text) => text.to_string(),
+            Expr::Group(inner) => format!("({})", inner.flat()),
+            Expr::Unary(operator, operand) => {
+                if double_negation(operator, operand) {
+                    format!("{operator} {}", operand.flat())
+                } else {
+                    format!("{operator}{}", operand.flat())
+                }
+            }
+            Expr::Binary(left, operator, _, right) => {
+                format!("{} {operator} {}", left.flat(), right.flat())
+            }
+            Expr::Assign(name, operator, value) => format!("{name} {operator} {}", value.flat()),
+        }
+    }
+}
+
+struct Printer<'c, 't, 'a> {
+    config: &'c FormatConfig,
+    source: &'a str,
+    tokens: &'t [Tok<'a>],
+    out: String,
+    indent: usize,
+    /// Something was printed on the current line
+    line_started: bool,
+}
+
+impl Printer<'_, '_, '_> {
+    fn column(&self) -> usize {
+        let line_start = self.out.rfind('\n').map_or(0, |idx| idx + 1);
+        self.out[line_start..].chars().count()
+    }
+    /// Starts a new line at the current indentation, after a blank line if there were several
+    /// line breaks in the source and `blank` allows it
+    fn newline(&mut self, newlines: usize, blank: bool) {
+        if self.line_started {
+            self.out.push('\n');
+            if blank && newlines > 1 {
+                self.out.push('\n');
+            }
+        }
+        self.out += &" ".repeat(self.indent * self.config.indent_width);
+        self.line_started = true;
+    }
+
+    /// Prints the comments before the token at `idx` and starts the line of the token.
+    /// Comments without a line break before them stay at the end of the previous line.
+    fn comments(&mut self, idx: usize, blank: bool) {
+        let tok = &self.tokens[idx];
+        let mut comments = tok.comments.iter().peekable();
+        while let Some(comment) = comments.next_if(|comment| comment.newlines == 0) {
+            if self.line_started {
+                self.out.push(' ');
+            } else {
+                self.newline(0, false);
+            }
+            self.out += comment.text;
+        }
+        let mut blank = blank && self.line_started;
+        for comment in comments {
+            self.newline(comment.newlines, blank);
+            self.out += comment.text;
+            blank = true;
+        }
+        let newlines = match tok.comments.last() {
+            Some(_) => tok.newlines,
+            None => tok.newlines.max(1),
+        };
+        self.newline(newlines, blank);
+    }
+
+    /// Prints the statements of a block or the whole file, with the comments up to the token at
+    /// `end`
+    fn items(&mut self, items: &[Item], end: usize) {
+        for (idx, item) in items.iter().enumerate() {
+            self.comments(item.first, idx > 0);
+            self.item(item);
+        }
+        let end = &self.tokens[end];
+        if end.comments.is_empty() {
+            return;
+        }
+        let mut comments = end.comments.iter().peekable();
+        while let Some(comment) = comments.next_if(|comment| comment.newlines == 0) {
+            if self.line_started {
+                self.out.push(' ');
+            }
+            self.out += comment.text;
+            self.line_started = true;
+        }
+        let mut blank = !items.is_empty();
+        for comment in comments {
+            self.newline(comment.newlines, blank);
+            self.out += comment.text;
+            blank = true;
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        let commented =
+            (item.first + 1..=item.last).any(|idx| !self.tokens[idx].comments.is_empty());
+        if commented && !matches!(item.stmt, Stmt::Block(_)) {
+            let start = self.tokens[item.first].token.span.start;
+            let end = self.tokens[item.last].token.span.end;
+            self.out += &self.source[start..end];
+            return;
+        }
+        match &item.stmt {
+            Stmt::Pragma(text) => self.out += text.trim_end(),
+            Stmt::Have(name, None) => self.out += &format!("have {name}"),
+            Stmt::Have(name, Some(init)) => {
+                self.out += &format!("have {name} := ");
+                self.expr(init, self.continuation(), 0);
+            }
+            Stmt::Info(expr) => {
+                self.out += "info ";
+                self.expr(expr, self.continuation(), 1);
+                self.out.push(';');
+            }
+            Stmt::Expr(expr) => self.expr(expr, self.continuation(), 0),
+            Stmt::Block(items) => {
+                self.out.push('{');
+                if items.is_empty() && self.tokens[item.last].comments.is_empty() {
+                    self.out.push('}');
+                    return;
+                }
+                self.indent += 1;
+                self.items(items, item.last);
+                self.indent -= 1;
+                self.newline(1, false);
+                self.out.push('}');
+            }
+        }
+    }
+
+    /// The column lines continuing the current statement start at
+    fn continuation(&self) -> usize {
+        (self.indent + 1) * self.config.indent_width
+    }
+
+    /// Prints `expr`, breaking it onto lines starting at column `indent` if it does not fit,
+    /// with `trailing` characters following it on the last line
+    fn expr(&mut self, expr: &Expr, indent: usize, trailing: usize) {
+        let flat = expr.flat();
+        if self.column() + flat.chars().count() + trailing <= self.config.max_width {
+            self.out += &flat;
+            return;
+        }
+        match expr {
+            Expr::Leaf(_) => self.out += &flat,
+            Expr::Group(inner) => {
+                self.out.push('(');
+                self.expr(inner, indent, 1 + trailing);
+                self.out.push(')');
+            }
+            Expr::Unary(operator, operand) => {
+                self.out += operator;
+                if double_negation(operator, operand) {
+                    self.out.push(' ');
+                }
+                self.expr(operand, indent, trailing);
+            }
+            Expr::Assign(name, operator, value) => {
+                self.out += &format!("{name} {operator} ");
+                self.expr(value, indent, trailing);
+            }
+            Expr::Binary(_, _, precedence, _) => {
+                // Operators of the same precedence are broken all at once, a + b - c being
+                // parsed as (a + b) - c
+                let mut operands = Vec::new();
+                let mut left = expr;
+                while let Expr::Binary(inner, operator, inner_precedence, right) = left {
+                    if inner_precedence != precedence {
+                        break;
+                    }
+                    operands.push((*operator, &**right));
+                    left = inner;
+                }
+                self.expr(left, indent + self.config.indent_width, 0);
+                for (idx, (operator, operand)) in operands.iter().rev().enumerate() {
+                    self.out.push('\n');
+                    self.out += &" ".repeat(indent);
+                    self.out += &format!("{operator} ");
+                    let trailing = if idx + 1 == operands.len() {
+                        trailing
+                    } else {
+                        0
+                    };
+                    self.expr(operand, indent + self.config.indent_width, trailing);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Golden tests: scripts that state what running them results in through comments.
+//!
+//! ```text
+//! info 1 + 2; // expect: 3
+//! info -"a";  // expect runtime error: [line 2] Operand of `-` must be a number, got string
+//! ```
+//!
+//! Every line that is printed is an `// expect: …`, every compile error an
+//! `// expect error: …` written the way it is reported, and a runtime error an
+//! `// expect runtime error: [line N] …`. Newlines in error messages are written as `\n`. Only
+//! the order of the comments matters, not the lines they are on.
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    scanner::{Scanner, TokenKind},
+    vm::{InterpretError, Vm},
+};
+
+/// One thing a script results in, as written after `//` in an expectation comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    Output(String),
+    CompileError(String),
+    RuntimeError(String),
+}
+
+/// An expectation comment in a script, with the location of its text after the `//`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub expectation: Expectation,
+    pub span: Range<usize>,
+}
+
+/// The result of running one script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub path: PathBuf,
+    pub expected: Vec<Expectation>,
+    pub actual: Vec<Expectation>,
+}
+
+/// One line of a [`diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a Expectation),
+    /// Expected but did not happen
+    Missing(&'a Expectation),
+    /// Happened but was not expected
+    Unexpected(&'a Expectation),
+}
+
+impl Expectation {
+    fn parse(comment: &str) -> Option<Self> {
+        let comment = comment.trim_start();
+        let (constructor, text): (fn(String) -> Self, _) =
+            if let Some(text) = comment.strip_prefix("expect:") {
+                (Expectation::Output, text)
+            } else if let Some(text) = comment.strip_prefix("expect error:") {
+                (Expectation::CompileError, text)
+            } else if let Some(text) = comment.strip_prefix("expect runtime error:") {
+                (Expectation::RuntimeError, text)
+            } else {
+                return None;
+            };
+        // Only the space after the colon is left out, so output with spaces around it can be expected
+        Some(constructor(
+            text.strip_prefix(' ').unwrap_or(text).to_owned(),
+        ))
+    }
+}
+
+impl Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expectation::Output(text) => write!(f, "expect: {text}"),
+            Expectation::CompileError(text) => write!(f, "expect error: {text}"),
+            Expectation::RuntimeError(text) => write!(f, "expect runtime error: {text}"),
+        }
+    }
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+/// Finds the expectation comments in `source`
+pub fn expectations(source: &str) -> Vec<Expected> {
+    Scanner::new(source)
+        .with_trivia()
+        .filter_map(|result| {
+            let token = result.ok()?;
+            let TokenKind::LineComment(text) = token.kind else {
+                return None;
+            };
+            Some(Expected {
+                expectation: Expectation::parse(text)?,
+                span: token.span.start + "//".len()..token.span.end,
+            })
+        })
+        .collect()
+}
+
+/// Runs `source`, giving back what it results in
+pub fn run(source: &str) -> Vec<Expectation> {
+    let mut vm = Vm::new(Vec::new());
+    let result = vm.interpret(source);
+    let output = String::from_utf8_lossy(&vm.into_output()).into_owned();
+    let mut actual: Vec<Expectation> = output
+        .lines()
+        .map(|line| Expectation::Output(line.to_owned()))
+        .collect();
+    match result {
+        Ok(()) => (),
+        Err(InterpretError::Compile(errors)) => actual.extend(
+            errors
+                .iter()
+                .map(|error| Expectation::CompileError(escape(&error.to_string()))),
+        ),
+        Err(InterpretError::Runtime(error)) => actual.push(Expectation::RuntimeError(format!(
+            "[line {}] {}",
+            error.line,
+            escape(&error.message)
+        ))),
+    }
+    actual
+}
+
+fn escape(message: &str) -> String {
+    message.replace('\n', "\\n")
+}
+
+/// Runs the script at `path` and compares it to its expectations
+pub fn check(path: &Path) -> io::Result<Outcome> {
+    let source = fs::read_to_string(path)?;
+    Ok(Outcome {
+        path: path.to_owned(),
+        expected: expectations(&source)
+            .into_iter()
+            .map(|expected| expected.expectation)
+            .collect(),
+        actual: run(&source),
+    })
+}
+
+/// Rewrites the expectation comments of `source` to be `actual`.
+///
+/// Comments that are already there are changed in order, ones that are left over are removed
+/// and missing ones are added to the end of the source.
+pub fn bless(source: &str, actual: &[Expectation]) -> String {
+    let existing = expectations(source);
+    let mut blessed = source.to_owned();
+    // Done from the back, so the spans of the earlier comments stay where they are
+    for expected in existing.iter().skip(actual.len()).rev() {
+        let comment = expected.span.start - "//".len();
+        let line_start = blessed[..comment].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = blessed[comment..]
+            .find('\n')
+            .map_or(blessed.len(), |idx| comment + idx);
+        let removed = if blessed[line_start..comment].trim().is_empty() {
+            line_start..(line_end + 1).min(blessed.len())
+        } else {
+            line_start + blessed[line_start..comment].trim_end().len()..line_end
+        };
+        blessed.replace_range(removed, "");
+    }
+    for (expected, actual) in existing.iter().zip(actual).rev() {
+        blessed.replace_range(expected.span.clone(), &format!(" {actual}"));
+    }
+    for actual in actual.iter().skip(existing.len()) {
+        if !blessed.is_empty() && !blessed.ends_with('\n') {
+            blessed.push('\n');
+        }
+        blessed += &format!("// {actual}\n");
+    }
+    blessed
+}
+
+/// Runs the script at `path` and writes what it results in back as its expectations
+pub fn bless_file(path: &Path) -> io::Result<Outcome> {
+    let source = fs::read_to_string(path)?;
+    let outcome = check(path)?;
+    let blessed = bless(&source, &outcome.actual);
+    // Adding comments to the end moves the end of the source, which errors can point at
+    let actual = run(&blessed);
+    let blessed = bless(&blessed, &actual);
+    if blessed != source {
+        fs::write(path, blessed)?;
+    }
+    Ok(Outcome { actual, ..outcome })
+}
+
+/// Finds all `.az` scripts in `path` and its subdirectories, or `path` itself if it is a file
+pub fn discover(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_owned()]);
+    }
+    let mut scripts = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            scripts.extend(discover(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "az") {
+            scripts.push(path);
+        }
+    }
+    scripts.sort();
+    Ok(scripts)
+}
+
+/// The shortest way to turn `expected` into `actual`
+pub fn diff<'a>(expected: &'a [Expectation], actual: &'a [Expectation]) -> Vec<DiffLine<'a>> {
+    // Length of the longest common subsequence of the suffixes starting at each pair of indices
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(DiffLine::Same(&expected[i]));
+            (i, j) = (i + 1, j + 1);
+        } else if j < actual.len() && (i == expected.len() || common[i][j + 1] >= common[i + 1][j])
+        {
+            lines.push(DiffLine::Unexpected(&actual[j]));
+            j += 1;
+        } else {
+            lines.push(DiffLine::Missing(&expected[i]));
+            i += 1;
+        }
+    }
+    lines
+}
+
+impl Display for DiffLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffLine::Same(expectation) => write!(f, "  {expectation}"),
+            DiffLine::Missing(expectation) => write!(f, "- {expectation}"),
+            DiffLine::Unexpected(expectation) => write!(f, "+ {expectation}"),
+        }
+    }
+}
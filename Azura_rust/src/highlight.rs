@@ -0,0 +1,208 @@
+//! Syntax highlighting of Azura code for terminals and HTML, based on the tokens of the scanner.
+//!
+//! All of the source is kept, including comments, whitespace and anything the scanner could
+//! not make sense of, which is highlighted as [`Highlight::Error`] instead of failing.
+use std::fmt::Write;
+
+use owo_colors::{OwoColorize, Style};
+
+use crate::{
+    keywords::{self, KEYWORDS},
+    scanner::{Scanner, TokenKind},
+};
+
+/// What a piece of source code is highlighted as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    Keyword,
+    /// `true`, `false` and `nil`
+    Constant,
+    Ident,
+    /// String and byte string literals
+    String,
+    /// Character and byte literals
+    Char,
+    Number,
+    Operator,
+    /// Brackets, `,`, `;`, `.` and `:`
+    Punctuation,
+    Comment,
+    DocComment,
+    Pragma,
+    /// Input the scanner could not turn into a token
+    Error,
+}
+
+/// The styles of the classes used by [`html`], for pages that embed [`html_fragment`]
+pub const CSS: &str = "\
+pre.azura { background: #fafafa; color: #383a42; padding: 1em; }
+.az-keyword { color: #a626a4; font-weight: bold; }
+.az-constant { color: #986801; }
+.az-ident { color: #383a42; }
+.az-string { color: #50a14f; }
+.az-char { color: #50a14f; }
+.az-number { color: #986801; }
+.az-operator { color: #0184bc; }
+.az-punctuation { color: #696c77; }
+.az-comment { color: #a0a1a7; font-style: italic; }
+.az-doc-comment { color: #4078f2; font-style: italic; }
+.az-pragma { color: #c18401; }
+.az-error { color: #e45649; text-decoration: wavy underline; }
+";
+
+impl Highlight {
+    /// How a token is highlighted, `None` for whitespace and the end of input
+    pub fn of(kind: &TokenKind) -> Option<Highlight> {
+        use TokenKind::*;
+        Some(match kind {
+            Keyword(
+                keywords::Keyword::True | keywords::Keyword::False | keywords::Keyword::Nil,
+            ) => Highlight::Constant,
+            Keyword(_) => Highlight::Keyword,
+            Ident(_) => Highlight::Ident,
+            Str(_) | ByteStr(_) => Highlight::String,
+            Char(_) | Byte(_) => Highlight::Char,
+            Integer(_) | BigInteger(_) | Float(_) => Highlight::Number,
+            OPar | CPar | OBrace | CBrace | OBracket | CBracket | Comma | Dot | Semicolon
+            | Colon => Highlight::Punctuation,
+            LineComment(_) | BlockComment(_) | Shebang(_) => Highlight::Comment,
+            DocComment(..) => Highlight::DocComment,
+            Pragma(_) => Highlight::Pragma,
+            Whitespace(_) | Newline | Eof => return None,
+            _ => Highlight::Operator,
+        })
+    }
+
+    /// The CSS class used by [`html`], without the `az-` prefix
+    pub fn name(self) -> &'static str {
+        match self {
+            Highlight::Keyword => "keyword",
+            Highlight::Constant => "constant",
+            Highlight::Ident => "ident",
+            Highlight::String => "string",
+            Highlight::Char => "char",
+            Highlight::Number => "number",
+            Highlight::Operator => "operator",
+            Highlight::Punctuation => "punctuation",
+            Highlight::Comment => "comment",
+            Highlight::DocComment => "doc-comment",
+            Highlight::Pragma => "pragma",
+            Highlight::Error => "error",
+        }
+    }
+
+    /// The terminal style used by [`ansi`]
+    pub fn style(self) -> Style {
+        let style = Style::new();
+        match self {
+            Highlight::Keyword => style.magenta().bold(),
+            Highlight::Constant => style.yellow(),
+            Highlight::Ident => style,
+            Highlight::String | Highlight::Char => style.green(),
+            Highlight::Number => style.yellow(),
+            Highlight::Operator => style.cyan(),
+            Highlight::Punctuation => style,
+            Highlight::Comment => style.bright_black().italic(),
+            Highlight::DocComment => style.blue().italic(),
+            Highlight::Pragma => style.bright_yellow(),
+            Highlight::Error => style.red().underline(),
+        }
+    }
+}
+
+/// Splits all of `source` into pieces with how they are highlighted
+pub fn highlights(source: &str) -> impl Iterator<Item = (Option<Highlight>, &str)> {
+    Scanner::new(source)
+        .with_trivia()
+        .with_keywords(&KEYWORDS)
+        .map(|result| match result {
+            Ok(token) => (Highlight::of(&token.kind), token.lexeme),
+            Err(error) => (Some(Highlight::Error), error.lexeme),
+        })
+        .filter(|(_, text)| !text.is_empty())
+}
+
+/// Highlights `source` with ANSI escape codes for a terminal
+pub fn ansi(source: &str) -> String {
+    let mut out = String::with_capacity(source.len() * 2);
+    for (highlight, text) in highlights(source) {
+        let Some(highlight) = highlight else {
+            out += text;
+            continue;
+        };
+        // Every line is styled on its own, so pagers showing only some of them still color them
+        let mut lines = text.split('\n');
+        if let Some(line) = lines.next() {
+            write_ansi(&mut out, highlight.style(), line);
+        }
+        for line in lines {
+            out.push('\n');
+            write_ansi(&mut out, highlight.style(), line);
+        }
+    }
+    out
+}
+
+fn write_ansi(out: &mut String, style: Style, text: &str) {
+    if !text.is_empty() {
+        let _ = write!(out, "{}", text.style(style));
+    }
+}
+
+/// Highlights `source` as a `<pre>` element with a `<span>` and CSS class per token, styled
+/// by [`CSS`]
+pub fn html_fragment(source: &str) -> String {
+    let mut out = String::with_capacity(source.len() * 3);
+    out += "<pre class=\"azura\"><code>";
+    for (highlight, text) in highlights(source) {
+        match highlight {
+            Some(highlight) => {
+                let _ = write!(
+                    out,
+                    "<span class=\"az-{}\">{}</span>",
+                    highlight.name(),
+                    escape_html(text)
+                );
+            }
+            None => out += &escape_html(text),
+        }
+    }
+    out += "</code></pre>";
+    out
+}
+
+/// Highlights `source` as a complete HTML page with the given title, including its styles
+pub fn html(source: &str, title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>
+{CSS}</style>
+</head>
+<body>
+{}
+</body>
+</html>
+",
+        escape_html(title),
+        html_fragment(source)
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&#39;",
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
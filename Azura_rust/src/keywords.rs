@@ -1,72 +1,269 @@
-use std::{collections::HashMap, ops::Index};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    hash::{BuildHasher, Hash},
+};
 
 use phf::phf_map;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Keyword {
+    And,
     Class,
+    Else,
+    False,
+    For,
+    Func,
+    Have,
+    If,
+    Info,
+    Nil,
+    Or,
+    Return,
+    Super,
+    This,
+    True,
+    While,
     Switch,
     Case,
 }
 
+impl Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Keyword::And => "and",
+            Keyword::Class => "class",
+            Keyword::Else => "else",
+            Keyword::False => "false",
+            Keyword::For => "for",
+            Keyword::Func => "func",
+            Keyword::Have => "have",
+            Keyword::If => "if",
+            Keyword::Info => "info",
+            Keyword::Nil => "nil",
+            Keyword::Or => "or",
+            Keyword::Return => "return",
+            Keyword::Super => "super",
+            Keyword::This => "this",
+            Keyword::True => "true",
+            Keyword::While => "while",
+            Keyword::Switch => "switch",
+            Keyword::Case => "case",
+        })
+    }
+}
+
+impl Keyword {
+    /// The first edition in which the keyword is reserved, before it the keyword is scanned as an
+    /// identifier
+    pub fn edition(self) -> Edition {
+        match self {
+            Keyword::Switch | Keyword::Case => Edition::V2,
+            _ => Edition::V1,
+        }
+    }
+    /// The keyword whose block a contextual keyword is reserved in, i.e. `case` is only a
+    /// keyword inside the braces of a `switch`
+    pub fn context(self) -> Option<Keyword> {
+        match self {
+            Keyword::Case => Some(Keyword::Switch),
+            _ => None,
+        }
+    }
+}
+
+/// A version of the language, which decides the keywords that are reserved.
+///
+/// Newer editions only ever add keywords, so scripts that use them as identifiers keep working
+/// under the edition they were written for. The default is [`Edition::V2`], which reserves
+/// everything that was a keyword before editions existed; scripts written for the C
+/// implementation can use `switch` as a name with `#edition 1`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edition {
+    /// The keywords of the C implementation
+    V1,
+    /// Adds `switch` along with the contextual `case`
+    #[default]
+    V2,
+}
+
+impl Edition {
+    pub const LATEST: Edition = Edition::V2;
+
+    /// The edition with the given number, as written in `#edition 2`
+    pub fn from_number(number: u64) -> Option<Edition> {
+        match number {
+            1 => Some(Edition::V1),
+            2 => Some(Edition::V2),
+            _ => None,
+        }
+    }
+    pub fn number(self) -> u64 {
+        match self {
+            Edition::V1 => 1,
+            Edition::V2 => 2,
+        }
+    }
+}
+
+impl Display for Edition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// Anything that can tell which keyword an identifier spells.
+///
+/// Whether a keyword is actually reserved is decided by the scanner, based on its
+/// [`Keyword::edition`] and [`Keyword::context`].
+pub trait KeywordTable {
+    fn keyword(&self, ident: &str) -> Option<Keyword>;
+}
+
+impl<T: KeywordTable + ?Sized> KeywordTable for &T {
+    fn keyword(&self, ident: &str) -> Option<Keyword> {
+        (**self).keyword(ident)
+    }
+}
+
+impl KeywordTable for phf::Map<&'static str, Keyword> {
+    fn keyword(&self, ident: &str) -> Option<Keyword> {
+        self.get(ident).copied()
+    }
+}
+
+impl<K: Borrow<str> + Eq + Hash, S: BuildHasher> KeywordTable for HashMap<K, Keyword, S> {
+    fn keyword(&self, ident: &str) -> Option<Keyword> {
+        self.get(ident).copied()
+    }
+}
+
+impl<K: Borrow<str> + Ord> KeywordTable for BTreeMap<K, Keyword> {
+    fn keyword(&self, ident: &str) -> Option<Keyword> {
+        self.get(ident).copied()
+    }
+}
+
+/// A list of spellings and keywords, searched from the start
+impl<K: AsRef<str>> KeywordTable for [(K, Keyword)] {
+    fn keyword(&self, ident: &str) -> Option<Keyword> {
+        self.iter()
+            .find(|(spelling, _)| spelling.as_ref() == ident)
+            .map(|(_, keyword)| *keyword)
+    }
+}
+
+/// Every keyword of the latest edition
 pub static KEYWORDS: phf::Map<&'static str, Keyword> = phf_map! {
+    "and" => Keyword::And,
     "class" => Keyword::Class,
+    "else" => Keyword::Else,
+    "false" => Keyword::False,
+    "for" => Keyword::For,
+    "func" => Keyword::Func,
+    "have" => Keyword::Have,
+    "if" => Keyword::If,
+    "info" => Keyword::Info,
+    "nil" => Keyword::Nil,
+    "or" => Keyword::Or,
+    "return" => Keyword::Return,
+    "super" => Keyword::Super,
+    "this" => Keyword::This,
+    "true" => Keyword::True,
+    "while" => Keyword::While,
     "switch" => Keyword::Switch,
     "case" => Keyword::Case,
 };
 
+/// Turns identifiers into keywords while scanning, created by [`Scanner::with_keywords`]
 pub struct ScannerWithKeywords<'a, K> {
     scanner: Scanner<'a>,
     keywords: K,
+    edition: Edition,
+    /// The keyword that opened each of the currently open blocks, if any
+    blocks: Vec<Option<Keyword>>,
+    /// A keyword that opens a context for the next `{`, i.e. a `switch`
+    pending: Option<Keyword>,
+    /// Whether a token other than a pragma, doc comment or trivia was scanned, after which
+    /// pragmas are ignored like the compiler rejects them
+    code_started: bool,
 }
 
-use lending_iterator::prelude::*;
-
 use crate::{
     error::ScannerError,
-    scanner::{Scanner, TokenKind},
+    pragmas::Pragma,
+    scanner::{Scanner, Token, TokenKind},
 };
-#[gat]
-impl<'iter> LendingIterator for ScannerWithKeywords<'iter, &'static phf::Map<&'iter str, Keyword>> {
-    type Item<'next>
-    where
-        Self: 'next,
-    = Result<TokenKind<'next>, ScannerError<'next>>;
-
-    fn next<'next>(
-        self: &'next mut ScannerWithKeywords<'iter, &'static phf::Map<&'iter str, Keyword>>,
-    ) -> Option<Result<TokenKind<'next>, ScannerError<'next>>> {
-        match self.scanner.next() {
-            Some(Ok(TokenKind::Ident(ident))) => Some(Ok(self
-                .keywords
-                .get(ident)
-                .map_or(TokenKind::Ident(ident), |keyword| {
-                    TokenKind::Keyword(*keyword)
-                }))),
-            other => other,
+
+impl<'a, K> ScannerWithKeywords<'a, K> {
+    /// Only reserves the keywords of `edition`, the default being [`Edition::V2`].
+    ///
+    /// An `#edition` pragma before any code switches to its edition from there on.
+    pub fn edition(self, edition: Edition) -> Self {
+        Self { edition, ..self }
+    }
+    fn is_reserved(&self, keyword: Keyword) -> bool {
+        keyword.edition() <= self.edition
+            && keyword
+                .context()
+                .is_none_or(|context| self.blocks.contains(&Some(context)))
+    }
+    /// Keeps track of the blocks contextual keywords are reserved in
+    fn track(&mut self, kind: &TokenKind<'a>) {
+        match kind {
+            TokenKind::OBrace => self.blocks.push(self.pending.take()),
+            TokenKind::CBrace => {
+                self.blocks.pop();
+            }
+            TokenKind::Keyword(Keyword::Switch) => self.pending = Some(Keyword::Switch),
+            // A statement ended without opening the block
+            TokenKind::Semicolon => self.pending = None,
+            // Keywords after `#edition` are those of the new edition, the compiler reports
+            // invalid pragmas and those after code
+            TokenKind::Pragma(text) => {
+                if let (false, Ok(Pragma::Edition(edition))) =
+                    (self.code_started, Pragma::parse(text))
+                {
+                    self.edition = edition;
+                }
+            }
+            _ => (),
+        }
+        if !matches!(
+            kind,
+            TokenKind::Pragma(_)
+                | TokenKind::DocComment(..)
+                | TokenKind::Whitespace(_)
+                | TokenKind::Newline
+                | TokenKind::LineComment(_)
+                | TokenKind::BlockComment(_)
+                | TokenKind::Shebang(_)
+        ) {
+            self.code_started = true;
         }
     }
 }
 
-#[gat]
-impl<'iter> LendingIterator for ScannerWithKeywords<'iter, &'iter HashMap<&'iter str, Keyword>> {
-    type Item<'next>
-    where
-        Self: 'next,
-    = Result<TokenKind<'next>, ScannerError<'next>>;
+impl<'a, K: KeywordTable> Iterator for ScannerWithKeywords<'a, K> {
+    type Item = Result<Token<'a>, ScannerError<'a>>;
 
-    fn next<'next>(
-        self: &'next mut ScannerWithKeywords<'iter, &'iter HashMap<&'iter str, Keyword>>,
-    ) -> Option<Result<TokenKind<'next>, ScannerError<'next>>> {
-        match self.scanner.next() {
-            Some(Ok(TokenKind::Ident(ident))) => Some(Ok(self
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut token = match self.scanner.next()? {
+            Ok(token) => token,
+            error => return Some(error),
+        };
+        if let TokenKind::Ident(ident) = token.kind {
+            if let Some(keyword) = self
                 .keywords
-                .get(ident)
-                .map_or(TokenKind::Ident(ident), |keyword| {
-                    TokenKind::Keyword(*keyword)
-                }))),
-            other => other,
+                .keyword(ident)
+                .filter(|keyword| self.is_reserved(*keyword))
+            {
+                token.kind = TokenKind::Keyword(keyword);
+            }
         }
+        self.track(&token.kind);
+        Some(Ok(token))
     }
 }
 
@@ -75,6 +272,10 @@ impl<'a> Scanner<'a> {
         ScannerWithKeywords {
             scanner: self,
             keywords,
+            edition: Edition::default(),
+            blocks: Vec::new(),
+            pending: None,
+            code_started: false,
         }
     }
 }
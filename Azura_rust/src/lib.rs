@@ -1,4 +1,14 @@
 #![allow(dead_code)]
+pub mod chunk;
+pub mod compiler;
 pub mod error;
+pub mod formatter;
+pub mod golden;
+pub mod highlight;
 pub mod keywords;
+pub mod pragmas;
+pub mod resolver;
 pub mod scanner;
+pub mod semantic;
+pub mod value;
+pub mod vm;
@@ -0,0 +1,227 @@
+//! The language features, answering requests about a [`Document`]
+use azura::{
+    compiler::{self, ErrorLocation},
+    keywords::{Keyword, KEYWORDS},
+    resolver::{self, Scope},
+    scanner::{Scanner, Span, TokenKind},
+    semantic::{self, Category},
+};
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, DocumentSymbol, Hover, HoverContents, MarkupContent,
+    MarkupKind, Range, SemanticToken, SemanticTokenModifier, SemanticTokenType,
+    SemanticTokensLegend, SymbolKind,
+};
+
+use crate::document::{utf16_len, Document};
+
+/// Errors of the scanner and the compiler
+pub fn diagnostics(document: &Document) -> Vec<Diagnostic> {
+    let diagnostic = |span: Span, message: String| Diagnostic {
+        range: document.range(span),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("azura".to_owned()),
+        message,
+        ..Diagnostic::default()
+    };
+    // The compiler only reports the first scanner error of a statement, so all of them are
+    // taken from the scanner instead
+    let mut diagnostics: Vec<_> = document
+        .scanner_errors()
+        .map(|error| {
+            diagnostic(
+                error.span,
+                compiler::CompileError::from_scanner(&error).message,
+            )
+        })
+        .collect();
+    if let Err(errors) = compiler::compile(document.text()) {
+        diagnostics.extend(
+            errors
+                .into_iter()
+                .filter(|error| error.location != ErrorLocation::Scanner)
+                .map(|error| diagnostic(error.span, error.message.trim().to_owned())),
+        );
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.range.start);
+    diagnostics
+}
+
+/// What the variable or keyword at `offset` is
+pub fn hover(document: &Document, offset: usize) -> Option<Hover> {
+    let source = document.text();
+    let token = Scanner::new(source)
+        .with_keywords(&KEYWORDS)
+        .flatten()
+        .find(|token| token.span.start <= offset && offset <= token.span.end)?;
+    let text = match token.kind {
+        TokenKind::Ident(name) => {
+            let resolution = resolver::resolve(source);
+            let description = match resolution.declaration_at(token.span.start) {
+                Some(idx) => {
+                    let declaration = &resolution.declarations[idx];
+                    let scope = match declaration.scope {
+                        Scope::Global => "Global",
+                        Scope::Local { .. } => "Local",
+                    };
+                    format!(
+                        "{scope} variable, declared on line {}",
+                        declaration.token.line
+                    )
+                }
+                None => "Global variable, which is never declared".to_owned(),
+            };
+            format!("```azura\nhave {name}\n```\n\n{description}")
+        }
+        TokenKind::Keyword(keyword) => {
+            format!("```azura\n{keyword}\n```\n\n{}", keyword_docs(keyword)?)
+        }
+        _ => return None,
+    };
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: text,
+        }),
+        range: Some(document.range(token.span)),
+    })
+}
+
+fn keyword_docs(keyword: Keyword) -> Option<&'static str> {
+    Some(match keyword {
+        Keyword::Have => "Declares a variable, i.e. `have x := 1`",
+        Keyword::Info => "Prints a value, i.e. `info x;`",
+        Keyword::True | Keyword::False => "A boolean",
+        Keyword::Nil => "The absence of a value",
+        _ => return None,
+    })
+}
+
+/// Where the variable at `offset` is declared
+pub fn definition(document: &Document, offset: usize) -> Option<Range> {
+    let resolution = resolver::resolve(document.text());
+    let idx = resolution.declaration_at(offset)?;
+    Some(document.range(resolution.declarations[idx].token.span))
+}
+
+/// Where the variable at `offset` is used, and with `declarations` also where it is declared
+pub fn references(document: &Document, offset: usize, declarations: bool) -> Vec<Range> {
+    let resolution = resolver::resolve(document.text());
+    let Some(idx) = resolution.declaration_at(offset) else {
+        return Vec::new();
+    };
+    let mut spans: Vec<Span> = resolution
+        .references_to(idx)
+        .map(|reference| reference.token.span)
+        .collect();
+    if declarations {
+        spans.extend(
+            resolution
+                .same_variable(idx)
+                .map(|declaration| declaration.token.span),
+        );
+    }
+    spans.sort_by_key(|span| span.start);
+    spans.into_iter().map(|span| document.range(span)).collect()
+}
+
+/// The variables declared in the document
+pub fn symbols(document: &Document) -> Vec<DocumentSymbol> {
+    let resolution = resolver::resolve(document.text());
+    resolution
+        .declarations
+        .iter()
+        .map(|declaration| {
+            let detail = match declaration.scope {
+                Scope::Global => "global",
+                Scope::Local { .. } => "local",
+            };
+            #[allow(deprecated)]
+            DocumentSymbol {
+                name: declaration.token.lexeme.to_owned(),
+                detail: Some(detail.to_owned()),
+                kind: SymbolKind::VARIABLE,
+                tags: None,
+                deprecated: None,
+                range: document.range(declaration.token.span),
+                selection_range: document.range(declaration.token.span),
+                children: None,
+            }
+        })
+        .collect()
+}
+
+/// The token types and modifiers of [`semantic_tokens`], in the order of their indices
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: vec![
+            SemanticTokenType::KEYWORD,
+            SemanticTokenType::VARIABLE,
+            SemanticTokenType::PARAMETER,
+            SemanticTokenType::FUNCTION,
+            SemanticTokenType::CLASS,
+            SemanticTokenType::PROPERTY,
+            SemanticTokenType::STRING,
+            SemanticTokenType::NUMBER,
+            SemanticTokenType::OPERATOR,
+            SemanticTokenType::COMMENT,
+        ],
+        token_modifiers: vec![
+            SemanticTokenModifier::DECLARATION,
+            SemanticTokenModifier::STATIC,
+        ],
+    }
+}
+
+const DECLARATION: u32 = 1 << 0;
+/// Set on globals, as the protocol has no token type for them
+const STATIC: u32 = 1 << 1;
+
+/// The tokens of the document as classified by [`semantic::classify`]
+pub fn semantic_tokens(document: &Document) -> Vec<SemanticToken> {
+    let mut tokens = Vec::new();
+    let mut previous = lsp_types::Position::default();
+    for classified in semantic::classify(document.text()) {
+        let token_type = match classified.category {
+            Category::Keyword => 0,
+            Category::GlobalVariable | Category::LocalVariable => 1,
+            Category::Parameter => 2,
+            Category::Function => 3,
+            Category::Class => 4,
+            Category::Field => 5,
+            Category::String => 6,
+            Category::Number => 7,
+            Category::Operator => 8,
+            Category::Comment => 9,
+        };
+        let mut modifiers = 0;
+        if classified.declaration {
+            modifiers |= DECLARATION;
+        }
+        if classified.category == Category::GlobalVariable {
+            modifiers |= STATIC;
+        }
+        // Tokens can not span several lines, so strings and comments are split up
+        let mut line_start = classified.token.span.start;
+        for line in classified.token.lexeme.split('\n') {
+            let position = document.position(line_start);
+            line_start += line.len() + 1;
+            let length = utf16_len(line.trim_end_matches('\r'));
+            if length == 0 {
+                continue;
+            }
+            let delta_line = position.line - previous.line;
+            tokens.push(SemanticToken {
+                delta_line,
+                delta_start: match delta_line {
+                    0 => position.character - previous.character,
+                    _ => position.character,
+                },
+                length,
+                token_type,
+                token_modifiers_bitset: modifiers,
+            });
+            previous = position;
+        }
+    }
+    tokens
+}
@@ -0,0 +1,92 @@
+//! An open file, with conversions between byte offsets and LSP positions
+use azura::scanner::{IncrementalScanner, ScannerError, Span, TextEdit};
+use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+
+/// The text of an open file as the editor sees it, kept up to date by its changes
+pub struct Document {
+    pub version: i32,
+    scanner: IncrementalScanner,
+    /// Byte offset of the start of every line
+    lines: Vec<usize>,
+}
+
+impl Document {
+    pub fn new(text: String, version: i32) -> Self {
+        let lines = line_starts(&text);
+        Document {
+            version,
+            scanner: IncrementalScanner::new(text),
+            lines,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.scanner.source()
+    }
+
+    /// The errors of the scanner, which are kept up to date as the document changes
+    pub fn scanner_errors(&self) -> impl Iterator<Item = ScannerError<'_>> {
+        self.scanner
+            .tokens()
+            .iter()
+            .filter_map(|lexed| lexed.as_ref().err())
+            .map(|error| error.as_error())
+    }
+
+    /// Applies a change sent by the editor, replacing everything if it has no range
+    pub fn apply(&mut self, change: &TextDocumentContentChangeEvent) {
+        let range = match change.range {
+            Some(range) => self.offset(range.start)..self.offset(range.end),
+            None => 0..self.text().len(),
+        };
+        let range = range.start.min(range.end)..range.end;
+        self.scanner.edit(TextEdit {
+            range,
+            text: &change.text,
+        });
+        self.lines = line_starts(self.text());
+    }
+
+    /// The byte offset of `position`, counting its character in UTF-16 code units like the
+    /// protocol does. Positions past the end of a line or the document are moved back to it.
+    pub fn offset(&self, position: Position) -> usize {
+        let Some(&start) = self.lines.get(position.line as usize) else {
+            return self.text().len();
+        };
+        let line = &self.text()[start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        let mut units = 0;
+        for (idx, ch) in line.char_indices() {
+            if units >= position.character as usize {
+                return start + idx;
+            }
+            units += ch.len_utf16();
+        }
+        start + line.trim_end_matches('\r').len()
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let line = self.lines.partition_point(|&start| start <= offset) - 1;
+        let start = self.lines[line];
+        Position {
+            line: line as u32,
+            character: utf16_len(&self.text()[start..offset]),
+        }
+    }
+
+    pub fn range(&self, span: Span) -> Range {
+        Range {
+            start: self.position(span.start),
+            end: self.position(span.end),
+        }
+    }
+}
+
+pub fn utf16_len(text: &str) -> u32 {
+    text.chars().map(char::len_utf16).sum::<usize>() as u32
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    let newlines = text.match_indices('\n').map(|(idx, _)| idx + 1);
+    std::iter::once(0).chain(newlines).collect()
+}
@@ -0,0 +1,239 @@
+//! `azura-lsp`, a language server for Azura files speaking the Language Server Protocol over
+//! stdio.
+//!
+//! It publishes the errors of the scanner and compiler as diagnostics whenever a file changes,
+//! and answers hover, go to definition, find references, document symbol and semantic token
+//! requests using the resolver.
+use std::{collections::HashMap, error::Error};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as LspNotification, PublishDiagnostics,
+    },
+    request::{
+        DocumentSymbolRequest, GotoDefinition, HoverRequest, References, Request as LspRequest,
+        SemanticTokensFullRequest,
+    },
+    DocumentSymbolResponse, GotoDefinitionResponse, HoverProviderCapability, InitializeResult,
+    Location, OneOf, PublishDiagnosticsParams, SemanticTokens, SemanticTokensFullOptions,
+    SemanticTokensOptions, SemanticTokensResult, ServerCapabilities, ServerInfo,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+mod analysis;
+mod document;
+
+use document::Document;
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+fn main() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let (id, _params) = connection.initialize_start()?;
+    let result = InitializeResult {
+        capabilities: capabilities(),
+        server_info: Some(ServerInfo {
+            name: "azura-lsp".to_owned(),
+            version: Some(env!("CARGO_PKG_VERSION").to_owned()),
+        }),
+    };
+    connection.initialize_finish(id, serde_json::to_value(result)?)?;
+
+    Server {
+        connection: &connection,
+        documents: HashMap::new(),
+    }
+    .run()?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        semantic_tokens_provider: Some(
+            SemanticTokensOptions {
+                legend: analysis::legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..SemanticTokensOptions::default()
+            }
+            .into(),
+        ),
+        ..ServerCapabilities::default()
+    }
+}
+
+struct Server<'c> {
+    connection: &'c Connection,
+    documents: HashMap<Uri, Document>,
+}
+
+impl Server<'_> {
+    /// Handles messages until the client shuts the server down
+    fn run(&mut self) -> Result<()> {
+        for message in &self.connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.request(request);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => {
+                    // There is no way to answer a malformed notification, so it is only logged
+                    if let Err(error) = self.notification(notification) {
+                        eprintln!("{error}");
+                    }
+                }
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => self.handle::<HoverRequest>(request, |server, params| {
+                let position = &params.text_document_position_params;
+                let (document, offset) = server.locate(position)?;
+                analysis::hover(document, offset)
+            }),
+            GotoDefinition::METHOD => self.handle::<GotoDefinition>(request, |server, params| {
+                let position = &params.text_document_position_params;
+                let (document, offset) = server.locate(position)?;
+                let range = analysis::definition(document, offset)?;
+                Some(GotoDefinitionResponse::Scalar(Location {
+                    uri: position.text_document.uri.clone(),
+                    range,
+                }))
+            }),
+            References::METHOD => self.handle::<References>(request, |server, params| {
+                let position = &params.text_document_position;
+                let (document, offset) = server.locate(position)?;
+                let declarations = params.context.include_declaration;
+                let locations = analysis::references(document, offset, declarations)
+                    .into_iter()
+                    .map(|range| Location {
+                        uri: position.text_document.uri.clone(),
+                        range,
+                    });
+                Some(locations.collect::<Vec<_>>())
+            }),
+            DocumentSymbolRequest::METHOD => {
+                self.handle::<DocumentSymbolRequest>(request, |server, params| {
+                    let document = server.documents.get(&params.text_document.uri)?;
+                    Some(DocumentSymbolResponse::Nested(analysis::symbols(document)))
+                })
+            }
+            SemanticTokensFullRequest::METHOD => {
+                self.handle::<SemanticTokensFullRequest>(request, |server, params| {
+                    let document = server.documents.get(&params.text_document.uri)?;
+                    Some(SemanticTokensResult::Tokens(SemanticTokens {
+                        result_id: None,
+                        data: analysis::semantic_tokens(document),
+                    }))
+                })
+            }
+            method => Err((
+                ErrorCode::MethodNotFound,
+                format!("Unknown request \"{method}\""),
+            )),
+        };
+        match result {
+            Ok(result) => Response::new_ok(id, result),
+            Err((code, message)) => Response::new_err(id, code as i32, message),
+        }
+    }
+
+    /// Answers a request with `handler`, the results of all requests handled here being optional
+    fn handle<R: LspRequest>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(&Self, R::Params) -> R::Result,
+    ) -> std::result::Result<serde_json::Value, (ErrorCode, String)>
+    where
+        R::Params: DeserializeOwned,
+        R::Result: Serialize,
+    {
+        let params = serde_json::from_value(request.params)
+            .map_err(|error| (ErrorCode::InvalidParams, error.to_string()))?;
+        Ok(serde_json::to_value(handler(self, params)).expect("results are valid JSON"))
+    }
+
+    /// The document and byte offset `position` points at
+    fn locate(&self, position: &TextDocumentPositionParams) -> Option<(&Document, usize)> {
+        let document = self.documents.get(&position.text_document.uri)?;
+        Some((document, document.offset(position.position)))
+    }
+
+    fn notification(&mut self, notification: Notification) -> Result<()> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = cast::<DidOpenTextDocument>(notification)?;
+                let document = params.text_document;
+                self.documents.insert(
+                    document.uri.clone(),
+                    Document::new(document.text, document.version),
+                );
+                document.uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = cast::<DidChangeTextDocument>(notification)?;
+                let Some(document) = self.documents.get_mut(&params.text_document.uri) else {
+                    return Ok(());
+                };
+                for change in &params.content_changes {
+                    document.apply(change);
+                }
+                document.version = params.text_document.version;
+                params.text_document.uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = cast::<DidCloseTextDocument>(notification)?;
+                self.documents.remove(&params.text_document.uri);
+                // The diagnostics of a closed file are cleared
+                return self.publish(params.text_document.uri, Vec::new(), None);
+            }
+            _ => return Ok(()),
+        };
+        let document = &self.documents[&uri];
+        let diagnostics = analysis::diagnostics(document);
+        let version = Some(document.version);
+        self.publish(uri, diagnostics, version)
+    }
+
+    fn publish(
+        &self,
+        uri: Uri,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        version: Option<i32>,
+    ) -> Result<()> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+}
+
+fn cast<N: LspNotification>(notification: Notification) -> Result<N::Params>
+where
+    N::Params: DeserializeOwned,
+{
+    Ok(serde_json::from_value(notification.params)?)
+}
@@ -1,37 +1,156 @@
 use std::{
-    collections::HashMap,
-    io::{stderr, stdout, Write},
+    env, fs,
+    io::{stderr, stdin, stdout, BufRead, Write},
+    process::exit,
 };
 
-use azura::{keywords, scanner::Scanner};
-use lending_iterator::LendingIterator;
-use owo_colors::OwoColorize;
+use azura::{
+    formatter::{self, FormatConfig},
+    golden::{self, DiffLine},
+    highlight, keywords,
+    scanner::{DumpFormat, Scanner, TokenRecord},
+    vm::{InterpretError, Vm},
+};
+use owo_colors::{OwoColorize, Stream::Stdout};
+
+const USAGE: &str = "Usage: azura [path]
+       azura tokens [--format=table|json|jsonl] [--trivia] <path>
+       azura highlight [--format=ansi|html] <path>
+       azura test [--bless] [path...]
+       azura fmt [--check] [--max-width=N] <path...>";
 
 fn main() {
-    let input = "// This is a comment
-switch idk {
-    case
-0b1010.a
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => repl(),
+        [command, args @ ..] if command == "tokens" => print_tokens(args),
+        [command, args @ ..] if command == "highlight" => print_highlighted(args),
+        [command, args @ ..] if command == "test" => run_tests(args),
+        [command, args @ ..] if command == "fmt" => format_files(args),
+        [path] => run_file(path),
+        _ => {
+            eprintln!("{USAGE}");
+            exit(64);
+        }
+    }
 }
-";
-    let scanner = Scanner::new(input);
+
+fn read_file(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|_| {
+        eprintln!("Could not read file \"{path}\".");
+        exit(74);
+    })
+}
+
+fn repl() {
+    let mut vm = Vm::new(stdout());
+    let mut lines = stdin().lock().lines();
+    loop {
+        print!(">>>");
+        let _ = stdout().flush();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+        if let Err(error) = vm.interpret(&line) {
+            eprintln!("{error}");
+        }
+    }
+}
+
+fn run_file(path: &str) {
+    let source = read_file(path);
+    let mut vm = Vm::new(stdout().lock());
+    match vm.interpret(&source) {
+        Ok(()) => (),
+        Err(error @ InterpretError::Compile(_)) => {
+            eprintln!("{error}");
+            exit(65);
+        }
+        Err(error @ InterpretError::Runtime(_)) => {
+            eprintln!("{error}");
+            exit(70);
+        }
+    }
+}
+
+/// Prints the tokens of a file, see [`DumpFormat`] for the formats
+fn print_tokens(args: &[String]) {
+    let mut format = DumpFormat::default();
+    let mut trivia = false;
+    let mut path = None;
+    for arg in args {
+        if let Some(name) = arg.strip_prefix("--format=") {
+            format = DumpFormat::parse(name).unwrap_or_else(|| {
+                eprintln!("Unknown token format \"{name}\", expected table, json or jsonl.");
+                exit(64);
+            });
+        } else if arg == "--trivia" {
+            trivia = true;
+        } else if arg.starts_with("--") || path.is_some() {
+            eprintln!("{USAGE}");
+            exit(64);
+        } else {
+            path = Some(arg);
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("{USAGE}");
+        exit(64);
+    };
+    let input = read_file(path);
+    let mut scanner = Scanner::new(&input);
+    if trivia {
+        scanner = scanner.with_trivia();
+    }
 
     let mut stdout = stdout().lock();
     let mut stderr = stderr().lock();
     let mut failure = false;
     let scanner = scanner.with_keywords(&keywords::KEYWORDS);
 
-    scanner.for_each(|token| {
-        let _ = match token {
-            Ok(token) => {
-                writeln!(stdout, "{token:?}")
-            }
+    if format == DumpFormat::Table {
+        let _ = writeln!(stdout, "{:>5} {:>4}  {:<18} LEXEME", "LINE", "COL", "KIND");
+    }
+    for (idx, token) in scanner.enumerate() {
+        let record = match &token {
+            Ok(token) => TokenRecord::from(token),
             Err(error) => {
                 failure = true;
-                writeln!(stderr, "{error}")
+                TokenRecord::from(error)
             }
         };
-    });
+        let _ = match (format, &token) {
+            (DumpFormat::Table, Ok(token)) if token.lexeme.is_empty() => {
+                writeln!(
+                    stdout,
+                    "{:>5} {:>4}  {}",
+                    token.line, token.pos, record.kind
+                )
+            }
+            (DumpFormat::Table, Ok(token)) => writeln!(
+                stdout,
+                "{:>5} {:>4}  {:<18} {}",
+                token.line,
+                token.pos,
+                record.kind,
+                printable(token.lexeme)
+            ),
+            (DumpFormat::Table, Err(error)) => writeln!(stderr, "{error}"),
+            (DumpFormat::Json, _) => {
+                let separator = if idx == 0 { "[\n" } else { ",\n" };
+                let record = serde_json::to_string(&record).expect("records are valid JSON");
+                write!(stdout, "{separator}  {record}")
+            }
+            (DumpFormat::JsonLines, _) => {
+                let record = serde_json::to_string(&record).expect("records are valid JSON");
+                writeln!(stdout, "{record}")
+            }
+        };
+    }
+    if format == DumpFormat::Json {
+        let _ = writeln!(stdout, "\n]");
+    }
     if failure {
         let _ = writeln!(
             stderr,
@@ -39,5 +158,164 @@ switch idk {
             "An error occured while scanning"
                 .if_supports_color(owo_colors::Stream::Stderr, |text| text.red())
         );
+        exit(65);
+    }
+}
+
+/// Prints a file with syntax highlighting, for a terminal or as an HTML page
+fn print_highlighted(args: &[String]) {
+    let (html, path) = match args {
+        [path] if !path.starts_with("--") => (false, path),
+        [format, path] if format == "--format=ansi" => (false, path),
+        [format, path] if format == "--format=html" => (true, path),
+        _ => {
+            eprintln!("{USAGE}");
+            exit(64);
+        }
+    };
+    let source = read_file(path);
+    let highlighted = if html {
+        highlight::html(&source, path)
+    } else {
+        highlight::ansi(&source)
+    };
+    let _ = stdout().lock().write_all(highlighted.as_bytes());
+}
+
+/// Escapes the characters of `lexeme` that would break up a row of the table, like newlines
+fn printable(lexeme: &str) -> String {
+    lexeme
+        .chars()
+        .map(|ch| {
+            if ch.is_control() {
+                ch.escape_debug().to_string()
+            } else {
+                ch.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Formats files in place, or with `--check` lists the ones that are not formatted
+fn format_files(args: &[String]) {
+    let mut config = FormatConfig::default();
+    let mut check = false;
+    let mut paths = Vec::new();
+    for arg in args {
+        if let Some(width) = arg.strip_prefix("--max-width=") {
+            config.max_width = width.parse().unwrap_or_else(|_| {
+                eprintln!("Invalid line width \"{width}\".");
+                exit(64);
+            });
+        } else if arg == "--check" {
+            check = true;
+        } else if arg.starts_with("--") {
+            eprintln!("Unknown flag \"{arg}\".\n{USAGE}");
+            exit(64);
+        } else {
+            paths.push(arg);
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{USAGE}");
+        exit(64);
+    }
+
+    let mut unformatted = false;
+    let mut failure = false;
+    for path in paths {
+        let source = read_file(path);
+        let formatted = match formatter::format(&source, &config) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{path}: {error}");
+                }
+                failure = true;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if check {
+            println!("{path}");
+            unformatted = true;
+        } else if fs::write(path, formatted).is_err() {
+            eprintln!("Could not write file \"{path}\".");
+            exit(74);
+        }
+    }
+    if failure {
+        exit(65);
+    }
+    if unformatted {
+        exit(1);
+    }
+}
+
+/// Runs the golden tests in the given files and directories, `tests` if there are none
+fn run_tests(args: &[String]) {
+    let bless = args.iter().any(|arg| arg == "--bless");
+    let mut paths: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--bless")
+        .collect();
+    if let Some(flag) = paths.iter().find(|path| path.starts_with("--")) {
+        eprintln!("Unknown flag \"{flag}\".\n{USAGE}");
+        exit(64);
+    }
+    if paths.is_empty() {
+        paths.push("tests");
+    }
+
+    let (mut passed, mut failed) = (0, 0);
+    for path in paths {
+        let scripts = golden::discover(path.as_ref()).unwrap_or_else(|_| {
+            eprintln!("Could not read directory \"{path}\".");
+            exit(74);
+        });
+        for script in scripts {
+            let outcome = if bless {
+                golden::bless_file(&script)
+            } else {
+                golden::check(&script)
+            };
+            let outcome = outcome.unwrap_or_else(|_| {
+                eprintln!("Could not read file \"{}\".", script.display());
+                exit(74);
+            });
+            if outcome.passed() {
+                passed += 1;
+                continue;
+            }
+            failed += 1;
+            let status = if bless { "BLESSED" } else { "FAILED" };
+            println!(
+                "{} {}",
+                status.if_supports_color(Stdout, |text| text.red()),
+                script.display()
+            );
+            for line in golden::diff(&outcome.expected, &outcome.actual) {
+                match line {
+                    DiffLine::Same(_) => println!("{line}"),
+                    DiffLine::Missing(_) => {
+                        println!("{}", line.if_supports_color(Stdout, |text| text.red()))
+                    }
+                    DiffLine::Unexpected(_) => {
+                        println!("{}", line.if_supports_color(Stdout, |text| text.green()))
+                    }
+                }
+            }
+        }
+    }
+    if bless {
+        println!("{passed} passed, {failed} blessed");
+    } else {
+        println!("{passed} passed, {failed} failed");
+    }
+    if failed > 0 && !bless {
+        exit(1);
     }
 }
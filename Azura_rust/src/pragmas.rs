@@ -0,0 +1,115 @@
+use std::fmt::Display;
+
+use crate::keywords::Edition;
+
+pub use crate::error::PragmaError;
+
+/// The settings a file makes with the `#` pragmas at its start
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pragmas {
+    /// Set by `#edition 1` or `#edition 2`
+    pub edition: Edition,
+    /// Set by `#strict`, which turns every lint that is not allowed into a compile error
+    pub strict: bool,
+    /// The lints turned off by `#allow(...)`
+    pub allowed: Vec<Lint>,
+}
+
+/// A check that is only made by `#strict` files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    /// A local variable that is never used, unless its name starts with `_`
+    Unused,
+}
+
+/// A single pragma, as written after the `#`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pragma {
+    Edition(Edition),
+    Strict,
+    Allow(Vec<Lint>),
+}
+
+impl Lint {
+    pub fn from_name(name: &str) -> Option<Lint> {
+        match name {
+            "unused" => Some(Lint::Unused),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Lint::Unused => "unused",
+        })
+    }
+}
+
+impl Pragma {
+    /// Parses the text of a [`TokenKind::Pragma`](crate::scanner::TokenKind::Pragma), which is
+    /// everything after the `#`
+    pub fn parse(text: &str) -> Result<Pragma, PragmaError> {
+        let text = text.trim();
+        let name_len = text
+            .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+            .unwrap_or(text.len());
+        let (name, rest) = text.split_at(name_len);
+        let rest = rest.trim_start();
+        match name {
+            "edition" => {
+                let edition = rest
+                    .parse()
+                    .ok()
+                    .and_then(Edition::from_number)
+                    .ok_or_else(|| PragmaError::UnknownEdition {
+                        edition: rest.to_owned(),
+                    })?;
+                Ok(Pragma::Edition(edition))
+            }
+            "strict" if rest.is_empty() => Ok(Pragma::Strict),
+            "allow" => {
+                let lints = rest
+                    .strip_prefix('(')
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .ok_or(PragmaError::Malformed {
+                        name: "allow",
+                        expected: "a list of lints like `#allow(unused)`",
+                    })?;
+                lints
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|lint| !lint.is_empty())
+                    .map(|lint| {
+                        Lint::from_name(lint).ok_or_else(|| PragmaError::UnknownLint {
+                            lint: lint.to_owned(),
+                        })
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Pragma::Allow)
+            }
+            "strict" => Err(PragmaError::Malformed {
+                name: "strict",
+                expected: "nothing after `#strict`",
+            }),
+            _ => Err(PragmaError::Unknown {
+                name: name.to_owned(),
+            }),
+        }
+    }
+}
+
+impl Pragmas {
+    pub fn apply(&mut self, pragma: Pragma) {
+        match pragma {
+            Pragma::Edition(edition) => self.edition = edition,
+            Pragma::Strict => self.strict = true,
+            Pragma::Allow(lints) => self.allowed.extend(lints),
+        }
+    }
+    /// Whether `lint` is checked, and raises a compile error when it fails
+    pub fn denies(&self, lint: Lint) -> bool {
+        self.strict && !self.allowed.contains(&lint)
+    }
+}
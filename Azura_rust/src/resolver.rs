@@ -0,0 +1,355 @@
+//! Scope analysis for editor tooling, resolving every use of a variable to its declaration.
+//!
+//! Locals are looked up the same way the compiler's `resolve_local` does, innermost scope first
+//! and including a local whose initializer is still being read. Anything that is not a local is
+//! a global, which is looked up by name when the program runs, so it is resolved to the closest
+//! global declaration before it or otherwise the first one after it. Unlike the compiler, the
+//! resolver does not stop at errors, so it works on code that is still being written.
+use crate::{
+    compiler::{compound_operation, Precedence},
+    keywords::{Keyword, KEYWORDS},
+    scanner::{Scanner, Span, Token, TokenKind},
+};
+
+/// Where a variable lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Global,
+    /// A local declared in a block, `depth` being the number of blocks around it
+    Local {
+        depth: usize,
+    },
+}
+
+/// How a variable is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    /// An assignment, including compound assignments like `+=`
+    Write,
+}
+
+/// A `have` declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration<'a> {
+    /// The name of the variable after `have`
+    pub token: Token<'a>,
+    pub scope: Scope,
+    /// The part of the source the variable can be used in, which for globals is all of it
+    pub visible: Span,
+}
+
+/// A use of a variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference<'a> {
+    pub token: Token<'a>,
+    pub access: Access,
+    pub scope: Scope,
+    /// The index of the declaration in [`Resolution::declarations`], `None` for globals that are
+    /// never declared
+    pub declaration: Option<usize>,
+}
+
+/// The variables of a source, in the order they appear in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resolution<'a> {
+    pub declarations: Vec<Declaration<'a>>,
+    pub references: Vec<Reference<'a>>,
+}
+
+impl<'a> Resolution<'a> {
+    /// The index of the declaration of the variable at byte `offset`, either in a declaration or
+    /// a reference
+    pub fn declaration_at(&self, offset: usize) -> Option<usize> {
+        let contains = |span: Span| span.start <= offset && offset <= span.end;
+        self.declarations
+            .iter()
+            .position(|declaration| contains(declaration.token.span))
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .find(|reference| contains(reference.token.span))?
+                    .declaration
+            })
+    }
+
+    /// The declarations that are the same variable as the declaration at `idx`, which are all the
+    /// global declarations with the same name for a global
+    pub fn same_variable(&self, idx: usize) -> impl Iterator<Item = &Declaration<'a>> {
+        let declaration = &self.declarations[idx];
+        self.declarations
+            .iter()
+            .enumerate()
+            .filter(move |(other_idx, other)| match declaration.scope {
+                Scope::Global => {
+                    other.scope == Scope::Global && other.token.lexeme == declaration.token.lexeme
+                }
+                Scope::Local { .. } => *other_idx == idx,
+            })
+            .map(|(_, other)| other)
+    }
+
+    /// The uses of the variable declared at `idx`, including those of other global declarations
+    /// of it
+    pub fn references_to(&self, idx: usize) -> impl Iterator<Item = &Reference<'a>> {
+        let declaration = &self.declarations[idx];
+        self.references
+            .iter()
+            .filter(move |reference| match declaration.scope {
+                Scope::Global => {
+                    reference.scope == Scope::Global
+                        && reference.token.lexeme == declaration.token.lexeme
+                }
+                Scope::Local { .. } => reference.declaration == Some(idx),
+            })
+    }
+}
+
+/// Finds all declarations and uses of variables in `source`
+pub fn resolve(source: &str) -> Resolution<'_> {
+    let mut tokens = Scanner::new(source)
+        .with_keywords(&KEYWORDS)
+        .flatten()
+        .filter(|token| !matches!(token.kind, TokenKind::DocComment(..) | TokenKind::Pragma(_)))
+        .collect::<Vec<_>>();
+    // The scanner always ends with the end of input, unless it gave up on an error before
+    if tokens
+        .last()
+        .is_none_or(|token| token.kind != TokenKind::Eof)
+    {
+        tokens.push(Token {
+            kind: TokenKind::Eof,
+            lexeme: "",
+            line: tokens.last().map_or(1, |token| token.line),
+            pos: 1,
+            span: Span {
+                start: source.len(),
+                end: source.len(),
+            },
+        });
+    }
+    let mut resolver = Resolver {
+        tokens: &tokens,
+        current: 0,
+        locals: Vec::new(),
+        globals: Vec::new(),
+        depth: 0,
+        end: source.len(),
+        resolution: Resolution::default(),
+    };
+    while resolver.kind() != &TokenKind::Eof {
+        resolver.declaration();
+    }
+    for (_, idx) in resolver.locals.drain(..) {
+        resolver.resolution.declarations[idx].visible.end = source.len();
+    }
+
+    let Resolver {
+        mut resolution,
+        globals,
+        ..
+    } = resolver;
+    let mut resolved = Vec::new();
+    for reference in &resolution.references {
+        if reference.scope != Scope::Global {
+            continue;
+        }
+        let named = globals.iter().filter(|(idx, _)| {
+            resolution.declarations[*idx].token.lexeme == reference.token.lexeme
+        });
+        let before = named
+            .clone()
+            .take_while(|(_, defined)| *defined <= reference.token.span.start)
+            .last();
+        resolved.push(before.or(named.clone().next()).map(|(idx, _)| *idx));
+    }
+    let references = resolution
+        .references
+        .iter_mut()
+        .filter(|reference| reference.scope == Scope::Global);
+    for (reference, declaration) in references.zip(resolved) {
+        reference.declaration = declaration;
+    }
+    resolution
+}
+
+/// Walks the grammar of the compiler, skipping over anything it does not expect
+struct Resolver<'t, 'a> {
+    tokens: &'t [Token<'a>],
+    current: usize,
+    /// The locals in scope with the index of their declaration, innermost last
+    locals: Vec<(usize, usize)>,
+    /// The global declarations with the offset after their initializer, from where on a use of
+    /// the name refers to them
+    globals: Vec<(usize, usize)>,
+    depth: usize,
+    /// The length of the source
+    end: usize,
+    resolution: Resolution<'a>,
+}
+
+impl<'a> Resolver<'_, 'a> {
+    fn kind(&self) -> &TokenKind<'a> {
+        &self.tokens[self.current].kind
+    }
+    fn advance(&mut self) -> Token<'a> {
+        let token = self.tokens[self.current].clone();
+        if token.kind != TokenKind::Eof {
+            self.current += 1;
+        }
+        token
+    }
+    fn matches(&mut self, kind: &TokenKind) -> bool {
+        let matches = self.kind() == kind;
+        if matches {
+            self.advance();
+        }
+        matches
+    }
+
+    fn declaration(&mut self) {
+        match self.kind() {
+            TokenKind::Keyword(Keyword::Have) => {
+                self.advance();
+                let mut declared = None;
+                if let TokenKind::Ident(_) = self.kind() {
+                    let token = self.advance();
+                    declared = Some(self.declare(token));
+                }
+                if self.matches(&TokenKind::Walrus) {
+                    self.expression();
+                }
+                if let Some(idx) = declared.filter(|_| self.depth == 0) {
+                    self.globals
+                        .push((idx, self.tokens[self.current].span.start));
+                }
+            }
+            TokenKind::Keyword(Keyword::Info) => {
+                self.advance();
+                self.expression();
+                self.matches(&TokenKind::Semicolon);
+            }
+            TokenKind::OBrace => {
+                self.advance();
+                self.depth += 1;
+                while !matches!(self.kind(), TokenKind::CBrace | TokenKind::Eof) {
+                    self.declaration();
+                }
+                let end = self.tokens[self.current].span.end;
+                self.matches(&TokenKind::CBrace);
+                self.depth -= 1;
+                while let Some(&(depth, idx)) = self.locals.last() {
+                    if depth <= self.depth {
+                        break;
+                    }
+                    self.resolution.declarations[idx].visible.end = end;
+                    self.locals.pop();
+                }
+            }
+            _ => {
+                let start = self.current;
+                self.expression();
+                if self.current == start {
+                    self.advance();
+                }
+            }
+        }
+    }
+    /// Adds a declaration of `token`, giving back its index
+    fn declare(&mut self, token: Token<'a>) -> usize {
+        let idx = self.resolution.declarations.len();
+        let scope = match self.depth {
+            0 => Scope::Global,
+            depth => {
+                self.locals.push((depth, idx));
+                Scope::Local { depth }
+            }
+        };
+        let visible = match scope {
+            Scope::Global => Span {
+                start: 0,
+                end: self.end,
+            },
+            Scope::Local { .. } => token.span,
+        };
+        self.resolution.declarations.push(Declaration {
+            token,
+            scope,
+            visible,
+        });
+        idx
+    }
+
+    fn expression(&mut self) {
+        self.parse_precedence(Precedence::Assignment);
+    }
+    fn parse_precedence(&mut self, precedence: Precedence) {
+        let can_assign = precedence <= Precedence::Assignment;
+        match self.kind().clone() {
+            TokenKind::OPar => {
+                self.advance();
+                self.expression();
+                self.matches(&TokenKind::CPar);
+            }
+            TokenKind::Sub | TokenKind::Bang => {
+                self.advance();
+                self.parse_precedence(Precedence::Unary);
+            }
+            TokenKind::Ident(_) => {
+                let token = self.advance();
+                let assignment = self.kind() == &TokenKind::Reassignment
+                    || compound_operation(self.kind()).is_some();
+                let access = if can_assign && assignment {
+                    Access::Write
+                } else {
+                    Access::Read
+                };
+                self.reference(token, access);
+                if access == Access::Write {
+                    self.advance();
+                    self.expression();
+                }
+            }
+            TokenKind::Str(_)
+            | TokenKind::Char(_)
+            | TokenKind::Byte(_)
+            | TokenKind::ByteStr(_)
+            | TokenKind::Integer(_)
+            | TokenKind::Float(_)
+            | TokenKind::BigInteger(_)
+            | TokenKind::Keyword(Keyword::True | Keyword::False | Keyword::Nil) => {
+                self.advance();
+            }
+            // Statements and closing brackets end the expression, anything else is skipped
+            TokenKind::Keyword(_) | TokenKind::OBrace | TokenKind::CBrace | TokenKind::Eof => {
+                return
+            }
+            _ => {
+                self.advance();
+                return;
+            }
+        }
+        while precedence <= Precedence::of(self.kind()) {
+            let operator = Precedence::of(self.kind());
+            self.advance();
+            self.parse_precedence(operator.next());
+        }
+    }
+    fn reference(&mut self, token: Token<'a>, access: Access) {
+        let local = self
+            .locals
+            .iter()
+            .rev()
+            .find(|(_, idx)| self.resolution.declarations[*idx].token.lexeme == token.lexeme);
+        let (scope, declaration) = match local {
+            Some(&(depth, idx)) => (Scope::Local { depth }, Some(idx)),
+            None => (Scope::Global, None),
+        };
+        self.resolution.references.push(Reference {
+            token,
+            access,
+            scope,
+            declaration,
+        });
+    }
+}
@@ -1,95 +1,250 @@
+mod cursor;
+mod dump;
+mod incremental;
+mod number;
+mod stream;
+mod string;
 mod tokens;
-use std::{
-    borrow::Cow,
-    iter::{Enumerate, Peekable},
-    str::Bytes,
-};
-
-use lending_iterator::LendingIterator;
+use std::borrow::Cow;
 
-pub use self::tokens::TokenKind;
-pub use crate::error::{ScannerError, ScannerErrorKind};
+use self::cursor::Cursor;
+pub use self::dump::{DumpFormat, TokenRecord};
+pub use self::incremental::{IncrementalScanner, Lexed, TextEdit};
+pub use self::stream::{Chunks, ReadChunks, StreamError, StreamScanner};
+use self::string::{ScannedStr, StrError};
+pub use self::tokens::{
+    BigIntLiteral, CommentStyle, OwnedToken, Span, StrLiteral, StrStyle, Token, TokenKind,
+};
+use crate::error::radix_name;
+pub use crate::error::{LiteralError, OwnedScannerError, ScannerError, ScannerErrorKind};
 
 #[derive(Debug)]
 pub struct Scanner<'a> {
     source: &'a str,
     line: usize,
+    /// Emit whitespace and comments as tokens instead of skipping over them
+    trivia: bool,
+    /// Byte offset of `source` in the original input
+    offset: usize,
+    /// Byte offset of the first character of the current line in the original input
+    line_start: usize,
+    /// Set once the [`TokenKind::Eof`] token has been yielded
+    finished: bool,
+    /// The last token apart from trivia was a `.`, so a number right after it is a field index
+    after_dot: bool,
+    /// A token apart from trivia was found on the current line, so a `#` is not a pragma
+    code_on_line: bool,
 }
 
-struct ByteWrapper<'a>(Peekable<Enumerate<Bytes<'a>>>);
-impl<'a> ByteWrapper<'a> {
-    fn new(iter: Peekable<Enumerate<Bytes<'a>>>) -> Self {
-        Self(iter)
+impl<'a> Scanner<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            line: 1,
+            trivia: false,
+            offset: 0,
+            line_start: 0,
+            finished: false,
+            after_dot: false,
+            code_on_line: false,
+        }
     }
-    fn next_byte(&mut self) -> Option<u8> {
-        self.0.next().map(|(_, b)| b)
+    /// Makes the scanner lossless: whitespace, newlines and comments are yielded as
+    /// [`TokenKind::Whitespace`], [`TokenKind::Newline`], [`TokenKind::LineComment`] and
+    /// [`TokenKind::BlockComment`], so concatenating the lexemes of all tokens and errors gives
+    /// back the original source.
+    pub fn with_trivia(self) -> Self {
+        Self {
+            trivia: true,
+            ..self
+        }
     }
-    fn next_idx(&mut self) -> Option<usize> {
-        self.0.next().map(|(idx, _)| idx)
+    /// Continues scanning in `source`, which has to be the input starting where `self` is
+    fn resume<'b>(&self, source: &'b str) -> Scanner<'b> {
+        Scanner {
+            source,
+            line: self.line,
+            trivia: self.trivia,
+            offset: self.offset,
+            line_start: self.line_start,
+            finished: false,
+            after_dot: self.after_dot,
+            code_on_line: self.code_on_line,
+        }
     }
-    fn next_both(&mut self) -> Option<(usize, u8)> {
-        self.0.next()
+    /// Registers a newline found at `idx` in the remaining source
+    fn newline(&mut self, idx: usize) {
+        self.line += 1;
+        self.line_start = self.offset + idx + 1;
+        self.code_on_line = false;
     }
-    fn peek(&mut self) -> Option<u8> {
-        self.0.peek().map(|(_, b)| *b)
+    /// Moves `bytes` to the end of the current line, or to the start of a `//` comment on it if
+    /// `comments` is set, returning where it ends up
+    fn skip_line(&self, bytes: &mut Cursor<'a>, comments: bool) -> usize {
+        let mut end = bytes.line_end();
+        if comments {
+            let line = &self.source[bytes.pos()..end];
+            end = line.find("//").map_or(end, |comment| bytes.pos() + comment);
+        }
+        bytes.seek(end);
+        end
     }
-    fn peek_idx(&mut self) -> Option<usize> {
-        self.0.peek().map(|(idx, _)| *idx)
+    /// Turns a scanned string, character or byte literal into its token, moving `bytes` past it
+    #[allow(clippy::result_large_err)]
+    fn string(
+        &mut self,
+        pos: usize,
+        string: ScannedStr<'a>,
+        bytes: &mut Cursor<'a>,
+    ) -> Result<TokenKind<'a>, ScannerError<'a>> {
+        let line = self.line;
+        for idx in memchr::memchr_iter(b'\n', &self.source.as_bytes()[pos..string.end]) {
+            self.newline(pos + idx);
+        }
+        bytes.seek(string.end);
+        let context = self.source.get(pos..string.end);
+        let name = string.name;
+        let (kind, pos, message) = match string.value {
+            Ok(token) => return Ok(token),
+            Err(StrError::Unterminated { token }) => (
+                ScannerErrorKind::Unmatched { token },
+                pos,
+                format!("Unterminated {name}"),
+            ),
+            // Both point at the offending part rather than the start of the literal
+            Err(StrError::InvalidEscape { sequence, at }) => (
+                ScannerErrorKind::InvalidEscape { sequence },
+                at,
+                format!("Unknown escape sequence `{sequence}`"),
+            ),
+            Err(StrError::Literal { error, at }) => (
+                ScannerErrorKind::IncorrectLiteral { error },
+                at,
+                format!("Failed to parse {name}"),
+            ),
+        };
+        Err(ScannerError {
+            kind,
+            line,
+            pos,
+            message: Some(Cow::Owned(message)),
+            context,
+            lexeme: "",
+            span: Span::default(),
+        })
     }
-}
-
-impl<'a> Scanner<'a> {
-    pub fn new(source: &'a str) -> Self {
-        Self { source, line: 1 }
+    /// The token marking the end of input, with an empty span right after the last character
+    fn eof(&mut self) -> Option<Result<Token<'a>, ScannerError<'a>>> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        let end = self.offset + self.source.len();
+        Some(Ok(Token {
+            kind: TokenKind::Eof,
+            lexeme: "",
+            line: self.line,
+            pos: end - self.line_start + 1,
+            span: Span { start: end, end },
+        }))
     }
 }
-use lending_iterator::prelude::*;
-#[gat]
-impl<'iter> LendingIterator for Scanner<'iter> {
-    type Item<'next>
-    where
-        Self: 'next,
-    = Result<TokenKind<'next>, ScannerError<'next>>;
+/// Tokens borrow from the source rather than from the scanner, so they can be kept around while
+/// scanning goes on, i.e. the compiler holding on to the previous and current token.
+impl<'a> Iterator for Scanner<'a> {
+    type Item = Result<Token<'a>, ScannerError<'a>>;
 
-    fn next<'next>(
-        self: &'next mut Scanner<'iter>,
-    ) -> Option<Result<TokenKind<'next>, ScannerError<'next>>> {
-        let mut bytes = ByteWrapper::new(self.source.bytes().enumerate().peekable());
-        let out = 'mainloop: loop {
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = Cursor::new(self.source, 0);
+        let mut start;
+        let mut line;
+        let mut line_start;
+        let out = loop {
             use TokenKind::*;
-            let Some((pos,byte)) = bytes.next_both() else {
-                return None;
+            let pos = bytes.pos();
+            let Some(byte) = bytes.bump() else {
+                // Skipped whitespace and comments still count as consumed
+                self.offset += pos;
+                self.source = &self.source[pos..];
+                return self.eof();
             };
+            start = pos;
+            line = self.line;
+            line_start = self.line_start;
             break match byte {
+                b'\n' if self.trivia => {
+                    self.newline(pos);
+                    Ok(Newline)
+                }
                 b'\n' => {
-                    self.line += 1;
-                    self.source = self.source.get(pos + 1..).unwrap();
-                    // Reset bytes iterator as it needs to stay in sync with the string
-                    bytes = ByteWrapper::new(self.source.bytes().enumerate().peekable());
+                    self.newline(pos);
                     continue;
                 }
                 b'(' => Ok(OPar),
                 b')' => Ok(CPar),
                 b'{' => Ok(OBrace),
                 b'}' => Ok(CBrace),
+                b'[' => Ok(OBracket),
+                b']' => Ok(CBracket),
                 b',' => Ok(Comma),
-                b'.' => Ok(Dot),
                 b';' => Ok(Semicolon),
+                // A shebang like `#!/usr/bin/env azura` has to be the very first thing in the file
+                b'#' if self.offset + pos == 0 && bytes.peek() == Some(b'!') => {
+                    let end = self.skip_line(&mut bytes, false);
+                    if self.trivia {
+                        break Ok(Shebang(&self.source[pos + 2..end]));
+                    }
+                    continue;
+                }
+                b'#' if !self.code_on_line
+                    && bytes.peek().is_some_and(|next| next.is_ascii_alphabetic()) =>
+                {
+                    // A comment after a pragma is still scanned as a comment
+                    let end = self.skip_line(&mut bytes, true);
+                    Ok(Pragma(self.source[pos + 1..end].trim_end()))
+                }
                 b'#' => Ok(Hashtag),
+                b'~' => Ok(BitNot),
+                b'.' => {
+                    // . .. ..=
+                    if bytes.eat(b'.') {
+                        if bytes.eat(b'=') {
+                            Ok(RangeInclusive)
+                        } else {
+                            Ok(Range)
+                        }
+                    } else {
+                        Ok(Dot)
+                    }
+                }
+                b'?' => {
+                    // ? ?? ??= ?.
+                    if bytes.eat(b'?') {
+                        if bytes.eat(b'=') {
+                            Ok(NullCoalesceAssign)
+                        } else {
+                            Ok(NullCoalesce)
+                        }
+                    } else if bytes.eat(b'.') {
+                        Ok(OptionalDot)
+                    } else {
+                        Ok(Question)
+                    }
+                }
                 b'+' => {
                     // + +=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_both();
+                    if bytes.eat(b'=') {
                         Ok(AddAssign)
                     } else {
                         Ok(Add)
                     }
                 }
                 b'-' => {
-                    // - -=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_byte();
+                    // - -= ->
+                    if bytes.eat(b'=') {
                         Ok(SubAssign)
+                    } else if bytes.eat(b'>') {
+                        Ok(Arrow)
                     } else {
                         Ok(Sub)
                     }
@@ -98,67 +253,113 @@ impl<'iter> LendingIterator for Scanner<'iter> {
                     // / /=
                     match bytes.peek() {
                         Some(b'=') => {
-                            bytes.next_byte();
+                            bytes.bump();
                             Ok(DivAssign)
                         }
-                        // normal comment
+                        // normal comment, or a doc comment if it starts with exactly three slashes
                         Some(b'/') => {
-                            while {
-                                let peek = bytes.peek();
-                                peek.is_some() && peek != Some(b'\n')
-                            } {
-                                bytes.next_both();
-                            }
+                            let doc = is_doc_comment(self.source.as_bytes(), pos, b'/');
                             // Not consuming the last newline as that would break the line count
+                            let end = bytes.line_end();
+                            bytes.seek(end);
+                            if doc {
+                                break Ok(DocComment(
+                                    &self.source[pos + 3..end],
+                                    CommentStyle::Line,
+                                ));
+                            }
+                            if self.trivia {
+                                break Ok(LineComment(&self.source[pos + 2..end]));
+                            }
                             continue;
                         }
-                        /* block comments */
+                        /* block comments, /* which can be nested */ */
                         Some(b'*') => {
-                            let mut current = bytes.next_byte();
-                            let mut peek = bytes.peek();
-                            while {
-                                peek.is_some() && !(current == Some(b'*') && peek == Some(b'/'))
-                            } {
-                                current = bytes.next_byte();
-                                peek = bytes.peek();
+                            bytes.bump();
+                            let doc = is_doc_comment(self.source.as_bytes(), pos, b'*');
+                            let start_line = self.line;
+                            let mut depth = 1usize;
+                            let mut closed = None;
+                            // Only newlines and the bytes of `/*` and `*/` matter in the body
+                            while let Some(byte) = bytes.find3(b'\n', b'/', b'*') {
+                                let idx = bytes.pos();
+                                bytes.bump();
+                                match byte {
+                                    b'\n' => self.newline(idx),
+                                    b'/' if bytes.eat(b'*') => depth += 1,
+                                    b'*' if bytes.eat(b'/') => {
+                                        depth -= 1;
+                                        if depth == 0 {
+                                            closed = Some(idx);
+                                            break;
+                                        }
+                                    }
+                                    _ => (),
+                                }
+                            }
+                            let Some(end) = closed else {
+                                // The rest of the input is part of the comment, so the only
+                                // place to recover at is the end of input
+                                bytes.end();
+                                break Err(ScannerError {
+                                    kind: ScannerErrorKind::UnterminatedBlockComment,
+                                    line: start_line,
+                                    pos,
+                                    message: Some("Unterminated block comment".into()),
+                                    context: self.source.get(pos..),
+                                    lexeme: "",
+                                    span: Span::default(),
+                                });
+                            };
+                            if doc {
+                                break Ok(DocComment(
+                                    &self.source[pos + 3..end],
+                                    CommentStyle::Block,
+                                ));
+                            }
+                            if self.trivia {
+                                break Ok(BlockComment(&self.source[pos + 2..end]));
                             }
-                            bytes.next_byte();
                             continue;
                         }
                         _ => Ok(Div),
                     }
                 }
                 b'*' => {
-                    // * *=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_both();
+                    // * *= ** **=
+                    if bytes.eat(b'=') {
                         Ok(MulAssign)
+                    } else if bytes.eat(b'*') {
+                        if bytes.eat(b'=') {
+                            Ok(PowAssign)
+                        } else {
+                            Ok(Pow)
+                        }
                     } else {
                         Ok(Mul)
                     }
                 }
                 b':' => {
                     // : :=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_byte();
+                    if bytes.eat(b'=') {
                         Ok(Walrus)
                     } else {
                         Ok(Colon)
                     }
                 }
                 b'=' => {
-                    // = ==
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_byte();
+                    // = == =>
+                    if bytes.eat(b'=') {
                         Ok(Equal)
+                    } else if bytes.eat(b'>') {
+                        Ok(FatArrow)
                     } else {
                         Ok(Reassignment)
                     }
                 }
                 b'!' => {
                     // ! !=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_both();
+                    if bytes.eat(b'=') {
                         Ok(NotEqual)
                     } else {
                         Ok(Bang)
@@ -166,221 +367,216 @@ impl<'iter> LendingIterator for Scanner<'iter> {
                 }
                 b'>' => {
                     // > >= >> >>=
-
-                    match bytes.peek() {
-                        Some(b'=') => {
-                            bytes.next_both();
-                            Ok(GreaterOrEqual)
-                        }
-                        Some(b'>') => {
-                            bytes.next_both();
-                            if bytes.peek() == Some(b'=') {
-                                bytes.next_both();
-                                Ok(RightShiftAssign)
-                            } else {
-                                Ok(RightShift)
-                            }
+                    if bytes.eat(b'=') {
+                        Ok(GreaterOrEqual)
+                    } else if bytes.eat(b'>') {
+                        if bytes.eat(b'=') {
+                            Ok(RightShiftAssign)
+                        } else {
+                            Ok(RightShift)
                         }
-                        _ => Ok(Greater),
+                    } else {
+                        Ok(Greater)
                     }
                 }
                 b'<' => {
                     // < <= << <<=
-                    match bytes.peek() {
-                        Some(b'=') => {
-                            bytes.next_both();
-                            Ok(LessOrEqual)
-                        }
-                        Some(b'<') => {
-                            bytes.next_both();
-                            if bytes.peek() == Some(b'=') {
-                                bytes.next_both();
-                                Ok(LeftShiftAssign)
-                            } else {
-                                Ok(LeftShift)
-                            }
+                    if bytes.eat(b'=') {
+                        Ok(LessOrEqual)
+                    } else if bytes.eat(b'<') {
+                        if bytes.eat(b'=') {
+                            Ok(LeftShiftAssign)
+                        } else {
+                            Ok(LeftShift)
                         }
-                        _ => Ok(Less),
+                    } else {
+                        Ok(Less)
                     }
                 }
                 b'%' => {
                     // % %=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_both();
+                    if bytes.eat(b'=') {
                         Ok(RemAssign)
                     } else {
                         Ok(Rem)
                     }
                 }
                 b'|' => {
-                    // | |=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_both();
+                    // | |= || ||=
+                    if bytes.eat(b'=') {
                         Ok(BitOrAssign)
+                    } else if bytes.eat(b'|') {
+                        if bytes.eat(b'=') {
+                            Ok(LogicalOrAssign)
+                        } else {
+                            Ok(LogicalOr)
+                        }
                     } else {
                         Ok(BitOr)
                     }
                 }
                 b'^' => {
                     // ^ ^=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_both();
+                    if bytes.eat(b'=') {
                         Ok(BitXorAssign)
                     } else {
                         Ok(BitXor)
                     }
                 }
                 b'&' => {
-                    // & &=
-                    if bytes.peek() == Some(b'=') {
-                        bytes.next_both();
+                    // & &= && &&=
+                    if bytes.eat(b'=') {
                         Ok(BitAndAssign)
+                    } else if bytes.eat(b'&') {
+                        if bytes.eat(b'=') {
+                            Ok(LogicalAndAssign)
+                        } else {
+                            Ok(LogicalAnd)
+                        }
                     } else {
                         Ok(BitAnd)
                     }
                 }
-                // String handling logic
-                quote if matches!(quote, b'\'' | b'"') => {
-                    let Some(start) = bytes.peek_idx() else {
-                        break Err(ScannerError { line: self.line, pos,  message: Some("Untermiated string at the end of input".into()), kind: ScannerErrorKind::IncompleteToken {token: Some(Str(""))}, context: None })
-                    };
-                    let mut end = start;
-                    let mut current = None;
-                    // Used for escaping quotes with `\`
-                    while !(current != Some(b'\\') && bytes.peek() == Some(quote)) {
-                        current = bytes.next_byte();
-                        if current.is_none() {
-                            break 'mainloop Err(ScannerError {
-                                line: self.line,
-                                pos,
-                                kind: ScannerErrorKind::Unmatched {
-                                    token: Some(Str(&self.source[start - 1..end])),
-                                },
-                                context: Some(self.source.get(pos..end).unwrap()),
-                                message: Some(Cow::Borrowed("Unterminated string")),
-                            });
-                        };
-                        end += 1;
-                    }
-                    bytes.next_both(); // Consumes the final quote
-                                       // SAFETY: end should never be advanced past the of string
-                    Ok(Str(self.source.get(start..end).expect(
-                        "Somehow tried to get a string outside of the array",
-                    )))
+                b'\'' => {
+                    let string = string::scan_character(self.source, pos);
+                    self.string(pos, string, &mut bytes)
                 }
-                digit
-                    if matches!(
-                        digit,
-                        b'0' | b'1' | b'2' | b'3' | b'4' | b'5' | b'6' | b'7' | b'8' | b'9'
-                    ) =>
-                {
-                    // Handle integer radix prefixes i.e 0b for binary, 0x for hexadecimal,
-                    // 0o for octal
-                    let picked = 'ragixpick: {
-                        if digit == b'0' {
-                            let Some(suffix) = bytes.peek() else {
-                            break 'ragixpick None;
-                        };
-                            match suffix {
-                                b'b' => Some((2, 2, "binary")),
-                                b'x' => Some((16, 2, "hexadecimal")),
-                                b'o' => Some((8, 2, "octal")),
-                                _ => None,
-                            }
-                        } else {
-                            None
-                        }
-                    };
-                    let handled_suffix = picked.is_some();
-                    let (radix, skip, base_name) = picked.unwrap_or((10, 0, "decimal"));
-
-                    let mut float = false;
-
-                    (0..skip).for_each(|_| {
-                        bytes.next_both();
-                    });
-                    let start = pos + skip;
-                    let mut end = start;
-                    // Keep "walking" forward until EOF or anything marked in `numeric_terminator`
-                    while {
-                        let peek = bytes.peek();
-                        peek.is_some() && !matches!(peek, Some(ch) if numeric_terminator(ch) )
-                    } {
-                        float |= bytes.next_byte() == Some(b'.');
-                        end += 1;
-                    }
-                    let number = &self.source[start..=end];
-
-                    if float {
-                        if handled_suffix {
-                            Err(ScannerError {
-                                kind: ScannerErrorKind::IncorrectLiteral { parse_error: None },
-                                line: self.line,
-                                pos,
-                                message: Some(Cow::Owned(format!(
-                                    "literal prefix `{}` suggests the float to be {base_name}",
-                                    &self.source[pos..pos + skip]
-                                ))),
-                                context: self.source.get(pos..=end),
-                            })
-                        } else {
-                            match number.parse() {
-                                Ok(float) => Ok(Float(float)),
-                                Err(error) => Err(ScannerError {
-                                    kind: ScannerErrorKind::IncorrectLiteral {
-                                        parse_error: Some(Box::new(error)),
-                                    },
-                                    line: self.line,
-                                    pos,
-                                    message: Some("Failed to parse float literal".into()),
-                                    context: self.source.get(pos..=end),
-                                }),
-                            }
-                        }
+                b'"' => {
+                    let string = string::scan_quoted(self.source, pos);
+                    self.string(pos, string, &mut bytes)
+                }
+                digit if digit.is_ascii_digit() => {
+                    let number = if self.after_dot {
+                        number::scan_index(self.source, pos)
                     } else {
-                        match isize::from_str_radix(number, radix) {
-                            Ok(parsed) => Ok(Integer(parsed)),
-                            Err(error) => Err(ScannerError {
-                                kind: ScannerErrorKind::IncorrectLiteral {
-                                    parse_error: Some(Box::new(error)),
-                                },
-                                line: self.line,
-                                pos,
-                                message: Some(
-                                    format!("Failed to parse {base_name} integer literal").into(),
-                                ),
-                                context: self.source.get(pos..=end),
-                            }),
-                        }
-                    }
+                        number::scan(self.source, pos)
+                    };
+                    bytes.seek(number.end);
+                    let base_name = radix_name(number.radix);
+                    number.value.map_err(|(error, at)| ScannerError {
+                        message: Some(match error {
+                            LiteralError::FloatWithRadix { .. } => Cow::Owned(format!(
+                                "literal prefix `{}` suggests the float to be {base_name}",
+                                &self.source[pos..pos + 2]
+                            )),
+                            _ => Cow::Owned(format!("Failed to parse {base_name} literal")),
+                        }),
+                        kind: ScannerErrorKind::IncorrectLiteral { error },
+                        line: self.line,
+                        // Points at the offending character rather than the start of the literal
+                        pos: at,
+                        context: self.source.get(pos..number.end),
+                        lexeme: "",
+                        span: Span::default(),
+                    })
                 }
-                ch if ch.is_ascii_whitespace() => continue,
-                // fallback for identifiers
-                _ => {
-                    let start = pos;
-                    let mut end = start;
-                    // Keep "walking" forward until EOF or anything marked in `numeric_terminator`
-                    while {
-                        let peek = bytes.peek();
-                        peek.is_some() && !matches!(peek, Some(ch) if ch.is_ascii_whitespace() )
-                    } {
-                        end += 1;
-                        bytes.next_both();
+                ch if ch.is_ascii_whitespace() => {
+                    bytes.eat_while(|ch| ch.is_ascii_whitespace() && ch != b'\n');
+                    if self.trivia {
+                        break Ok(Whitespace(&self.source[pos..bytes.pos()]));
                     }
-                    let identifier = &self.source[start..=end];
-                    Ok(Ident(identifier))
+                    continue;
+                }
+                b'r' if string::is_raw(self.source, pos) => {
+                    let string = string::scan_raw(self.source, pos);
+                    self.string(pos, string, &mut bytes)
+                }
+                b'b' if string::is_byte_literal(self.source, pos) => {
+                    let string = string::scan_byte_literal(self.source, pos);
+                    self.string(pos, string, &mut bytes)
+                }
+                ch if is_identifier_start(ch) => {
+                    // Keep "walking" forward until EOF or anything that can't be in an identifier
+                    bytes.eat_while(is_identifier_continue);
+                    Ok(Ident(&self.source[pos..bytes.pos()]))
                 }
+                // Only ASCII bytes can end up here, so the rest of the input still starts on a
+                // character boundary after skipping it
+                character => Err(ScannerError {
+                    kind: ScannerErrorKind::UnexpectedCharacter {
+                        character: character as char,
+                    },
+                    line: self.line,
+                    pos,
+                    message: Some(Cow::Owned(format!(
+                        "Unexpected character `{}`",
+                        character as char
+                    ))),
+                    context: None,
+                    lexeme: "",
+                    span: Span::default(),
+                }),
             };
         };
-        if let Some(consumed) = bytes.next_idx() {
-            self.source = self.source.get(consumed..).unwrap_or_default()
-        } else {
-            self.source = "";
+        let consumed = bytes.pos();
+        let lexeme = &self.source[start..consumed];
+        let span = Span {
+            start: self.offset + start,
+            end: self.offset + consumed,
+        };
+        let offset = self.offset;
+        let source = self.source;
+        match &out {
+            Ok(
+                TokenKind::Whitespace(_)
+                | TokenKind::Newline
+                | TokenKind::LineComment(_)
+                | TokenKind::BlockComment(_)
+                | TokenKind::Shebang(_),
+            ) => (),
+            out => {
+                self.after_dot = matches!(out, Ok(TokenKind::Dot | TokenKind::OptionalDot));
+                self.code_on_line = true;
+            }
         }
-        Some(out)
+        self.source = &self.source[consumed..];
+        self.offset += consumed;
+        Some(match out {
+            Ok(kind) => Ok(Token {
+                kind,
+                lexeme,
+                line,
+                pos: span.start - line_start + 1,
+                span,
+            }),
+            // Errors are created with an empty lexeme and a position relative to the remaining
+            // source, as the recovery point and the line start are only known here
+            Err(error) => {
+                // An error can point past a newline inside the token, like an invalid escape in a
+                // multi-line string, which puts it on a later line than the token itself
+                let before_error = &source.as_bytes()[start..error.pos];
+                let (line, line_start) = match before_error.iter().rposition(|&b| b == b'\n') {
+                    Some(newline) => (
+                        line + before_error.iter().filter(|&&b| b == b'\n').count(),
+                        offset + start + newline + 1,
+                    ),
+                    None => (error.line, line_start),
+                };
+                Err(ScannerError {
+                    lexeme,
+                    line,
+                    pos: offset + error.pos - line_start + 1,
+                    span,
+                    ..error
+                })
+            }
+        })
     }
 }
 
-fn numeric_terminator(check: u8) -> bool {
-    check.is_ascii_whitespace() || !check.is_ascii_alphanumeric() && check != b'.'
+/// Checks whether the comment starting at `pos` is a doc comment, that is `///` or `/**`, but
+/// not `////`, `/***` or the empty block comment `/**/`
+fn is_doc_comment(source: &[u8], pos: usize, marker: u8) -> bool {
+    source.get(pos + 2) == Some(&marker)
+        && !matches!(source.get(pos + 3), Some(&next) if next == marker || next == b'/')
+}
+
+/// Bytes outside of ASCII are always treated as part of an identifier, which keeps multi-byte
+/// characters in one piece
+fn is_identifier_start(check: u8) -> bool {
+    check.is_ascii_alphabetic() || check == b'_' || !check.is_ascii()
+}
+
+fn is_identifier_continue(check: u8) -> bool {
+    is_identifier_start(check) || check.is_ascii_digit()
 }
@@ -0,0 +1,61 @@
+use memchr::{memchr, memchr3};
+
+/// A position in the bytes of the source, which the scanner moves forward over a token.
+///
+/// Unlike iterating over the bytes, jumping to any index is free, so the scanner can search for
+/// the end of a comment or string with `memchr` and continue right after it.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(super) fn new(source: &'a str, pos: usize) -> Self {
+        Self {
+            bytes: source.as_bytes(),
+            pos,
+        }
+    }
+    /// Index of the next byte
+    pub(super) fn pos(&self) -> usize {
+        self.pos
+    }
+    pub(super) fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+    pub(super) fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+    pub(super) fn bump(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+    /// Moves past the next byte if it is `expected`
+    pub(super) fn eat(&mut self, expected: u8) -> bool {
+        let found = self.peek() == Some(expected);
+        self.pos += usize::from(found);
+        found
+    }
+    pub(super) fn eat_while(&mut self, mut predicate: impl FnMut(u8) -> bool) {
+        let rest = &self.bytes[self.pos..];
+        self.pos += rest
+            .iter()
+            .position(|&byte| !predicate(byte))
+            .unwrap_or(rest.len());
+    }
+    /// Index of the end of the current line, which is the next `\n` or the end of input
+    pub(super) fn line_end(&self) -> usize {
+        memchr(b'\n', &self.bytes[self.pos..]).map_or(self.bytes.len(), |idx| self.pos + idx)
+    }
+    /// Moves to the next of the three bytes, giving back which one it is
+    pub(super) fn find3(&mut self, first: u8, second: u8, third: u8) -> Option<u8> {
+        let idx = memchr3(first, second, third, &self.bytes[self.pos..])?;
+        self.pos += idx;
+        self.peek()
+    }
+    pub(super) fn end(&mut self) {
+        self.pos = self.bytes.len();
+    }
+}
@@ -0,0 +1,118 @@
+//! Tokens as records for other tools, as printed by `azura tokens`
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{ScannerError, Token, TokenKind};
+use crate::error::CompileError;
+
+/// A token or scanner error as given to other tools. With `--format=json` the output of
+/// `azura tokens` is an array of records, with `--format=jsonl` every line is one record. Each
+/// record is an object with these fields:
+///
+/// | Field     | Type             | Description                                                   |
+/// |-----------|------------------|---------------------------------------------------------------|
+/// | `kind`    | string           | The name of the [`TokenKind`], i.e. `"Ident"`, or `"Error"`   |
+/// | `lexeme`  | string           | The exact source text                                         |
+/// | `line`    | number           | Line of the first character, starting at 1                    |
+/// | `column`  | number           | Column of the first byte, counted in bytes, starting at 1     |
+/// | `start`   | number           | Byte offset of the first byte in the source                   |
+/// | `end`     | number           | Byte offset after the last byte                               |
+/// | `value`   | see below        | The value of literals, identifiers, keywords and comments     |
+/// | `message` | string           | Only on errors, what is wrong                                 |
+///
+/// The `value` is left out for tokens without one. It is a string for the name of an `Ident`,
+/// the text of a `Str`, `Char` or `Keyword`, and the text of comments, pragmas and whitespace
+/// without their delimiters. It is a number for an `Integer`, `Float` or `Byte`, an array of
+/// numbers for the bytes of a `ByteStr`, and `{"digits": string, "radix": number}` for a
+/// `BigInteger`, with the digits written in the radix.
+///
+/// Errors the scanner recovers from are records of kind `"Error"` in between the tokens, and the
+/// last record is always the `Eof` token.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenRecord<'a> {
+    pub kind: &'static str,
+    pub lexeme: &'a str,
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// How `azura tokens` prints tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DumpFormat {
+    /// A line, column, kind and lexeme per token for reading, with errors on stderr
+    #[default]
+    Table,
+    /// A JSON array of [`TokenRecord`]s
+    Json,
+    /// A [`TokenRecord`] per line
+    JsonLines,
+}
+
+impl DumpFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "table" => Some(DumpFormat::Table),
+            "json" => Some(DumpFormat::Json),
+            "jsonl" => Some(DumpFormat::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&Token<'a>> for TokenRecord<'a> {
+    fn from(token: &Token<'a>) -> Self {
+        use TokenKind::*;
+        let value = match &token.kind {
+            Ident(text)
+            | DocComment(text, _)
+            | Pragma(text)
+            | Whitespace(text)
+            | LineComment(text)
+            | BlockComment(text)
+            | Shebang(text) => Some(json!(text)),
+            Str(literal) => Some(json!(literal.value())),
+            ByteStr(literal) => Some(json!(literal.bytes())),
+            Char(ch) => Some(json!(ch)),
+            Byte(byte) => Some(json!(byte)),
+            Integer(integer) => Some(json!(integer)),
+            BigInteger(literal) => Some(json!({
+                "digits": literal.digits.replace('_', ""),
+                "radix": literal.radix,
+            })),
+            Float(float) => Some(json!(float)),
+            Keyword(keyword) => Some(json!(keyword.to_string())),
+            _ => None,
+        };
+        TokenRecord {
+            kind: token.kind.name(),
+            lexeme: token.lexeme,
+            line: token.line,
+            column: token.pos,
+            start: token.span.start,
+            end: token.span.end,
+            value,
+            message: None,
+        }
+    }
+}
+
+impl<'a> From<&ScannerError<'a>> for TokenRecord<'a> {
+    fn from(error: &ScannerError<'a>) -> Self {
+        TokenRecord {
+            kind: "Error",
+            lexeme: error.lexeme,
+            line: error.line,
+            column: error.pos,
+            start: error.span.start,
+            end: error.span.end,
+            value: None,
+            message: Some(CompileError::from_scanner(error).message),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use std::ops::Range;
+
+use memchr::memrchr;
+
+use super::{
+    OwnedScannerError, OwnedToken, Scanner, ScannerError, ScannerErrorKind, Span, Token, TokenKind,
+};
+
+/// A token or error as kept by an [`IncrementalScanner`]
+pub type Lexed = Result<OwnedToken, OwnedScannerError>;
+
+/// A change to the source, replacing the bytes in `range` with `text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit<'a> {
+    pub range: Range<usize>,
+    pub text: &'a str,
+}
+
+/// Keeps the tokens of a source that is changed a bit at a time, like a file open in an editor.
+///
+/// An edit only re-lexes the lines around it, going back as far as a string or block comment
+/// containing it starts and forward until the tokens are the same as before again, which also
+/// covers edits that open or close a string or comment further away. The tokens are always the
+/// same as scanning all of [`IncrementalScanner::source`] again.
+#[derive(Debug, Clone)]
+pub struct IncrementalScanner {
+    source: String,
+    tokens: Vec<Lexed>,
+    trivia: bool,
+}
+
+impl IncrementalScanner {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self::scan(source.into(), false)
+    }
+    /// Keeps trivia as well, see [`Scanner::with_trivia`]
+    pub fn with_trivia(source: impl Into<String>) -> Self {
+        Self::scan(source.into(), true)
+    }
+    fn scan(source: String, trivia: bool) -> Self {
+        let mut scanner = Scanner::new(&source);
+        scanner.trivia = trivia;
+        let tokens = scanner.map(owned).collect();
+        Self {
+            source,
+            tokens,
+            trivia,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+    /// All tokens and errors of the source, ending with [`TokenKind::Eof`]
+    pub fn tokens(&self) -> &[Lexed] {
+        &self.tokens
+    }
+
+    /// Applies `edit` to the source and updates the tokens, giving back the indices of the tokens
+    /// that were scanned again. Everything outside of them is the same as before, apart from
+    /// being moved to the new position.
+    ///
+    /// # Panics
+    ///
+    /// If the range of `edit` is out of bounds or does not lie on character boundaries
+    pub fn edit(&mut self, edit: TextEdit) -> Range<usize> {
+        let TextEdit { range, text } = edit;
+        self.source.replace_range(range.clone(), text);
+        let edit_end = range.start + text.len();
+        let delta = edit_end as isize - range.end as isize;
+
+        let first = self.restart(range.start);
+        // Scanning starts right after the last token that is kept, from the same state
+        let (restart, line) = match first.checked_sub(1).map(|idx| &self.tokens[idx]) {
+            Some(Ok(token)) => {
+                let newlines = memchr::memchr_iter(b'\n', token.lexeme.as_bytes()).count();
+                (token.span.end, token.line + newlines)
+            }
+            _ => (0, 1),
+        };
+        let line_start =
+            memrchr(b'\n', &self.source.as_bytes()[..restart]).map_or(0, |idx| idx + 1);
+        let (after_dot, code_on_line) = state(self.tokens[..first].iter().rev(), line_start);
+        let scanner = Scanner {
+            source: &self.source[restart..],
+            line,
+            trivia: self.trivia,
+            offset: restart,
+            line_start,
+            finished: false,
+            after_dot,
+            code_on_line,
+        };
+
+        let mut scanned = Vec::new();
+        let mut reused = self.tokens.len();
+        let mut line_delta = 0;
+        // The last newline after the edit that was found so far, and where to search on from
+        let mut newline = None;
+        let mut searched = edit_end;
+        for result in scanner {
+            let result = owned(result);
+            let start = span(&result).start;
+            if start > searched {
+                let bytes = &self.source.as_bytes()[searched..start];
+                newline = memrchr(b'\n', bytes).map(|idx| searched + idx).or(newline);
+                searched = start;
+            }
+            if let Some(newline) = newline {
+                if let Some(old) = self.synced(&scanned, first, start, newline + 1, delta) {
+                    line_delta = line_of(&result) as isize - line_of(&self.tokens[old]) as isize;
+                    reused = old;
+                    break;
+                }
+            }
+            scanned.push(result);
+        }
+
+        for result in &mut self.tokens[reused..] {
+            let (span, line) = match result {
+                Ok(token) => (&mut token.span, &mut token.line),
+                Err(error) => (&mut error.span, &mut error.line),
+            };
+            span.start = span.start.wrapping_add_signed(delta);
+            span.end = span.end.wrapping_add_signed(delta);
+            *line = line.wrapping_add_signed(line_delta);
+        }
+        let changed = first..first + scanned.len();
+        self.tokens.splice(first..reused, scanned);
+        changed
+    }
+
+    /// Finds the first token that has to be scanned again for an edit at `pos`, which is the
+    /// one after the last token ending before the line of `pos`.
+    ///
+    /// Apart from searching for the end of a string, the scanner never looks past the end of a
+    /// line, so tokens on earlier lines do not depend on the edit. Comments are not kept outside
+    /// of trivia mode, so this always ends on a token rather than in the middle of a comment.
+    fn restart(&self, pos: usize) -> usize {
+        // An unterminated string searched the whole rest of the source for its end, which might
+        // be part of the edit
+        let open = self.tokens.iter().position(|result| {
+            result.as_ref().is_err_and(|error| {
+                matches!(
+                    error.as_error().kind,
+                    ScannerErrorKind::Unmatched { token: Some(_) }
+                )
+            })
+        });
+        let pos = match open {
+            Some(idx) => pos.min(span(&self.tokens[idx]).start),
+            None => pos,
+        };
+        let line_start = memrchr(b'\n', &self.source.as_bytes()[..pos]).map_or(0, |idx| idx + 1);
+        // The end of input is always scanned again
+        let before = &self.tokens[..self.tokens.len() - 1];
+        let kept = before.partition_point(|result| span(result).end <= line_start);
+        // The state after an error is not known, so scanning starts before it
+        before[..kept]
+            .iter()
+            .rposition(Result::is_ok)
+            .map_or(0, |idx| idx + 1)
+    }
+
+    /// Checks if the old tokens can be used again from a token scanned at `start`, on the line
+    /// starting at `line_start` after the edit, giving back the index of the old token there.
+    ///
+    /// Since the line starts after the edit, the old tokens on it are in the same column, and
+    /// the rest of the source is the same as before. What is left is that the old token has to
+    /// start at the same place, with the scanner in the same state.
+    fn synced(
+        &self,
+        scanned: &[Lexed],
+        first: usize,
+        start: usize,
+        line_start: usize,
+        delta: isize,
+    ) -> Option<usize> {
+        let old_start = start.wrapping_add_signed(-delta);
+        let old =
+            first + self.tokens[first..].partition_point(|result| span(result).start < old_start);
+        if self
+            .tokens
+            .get(old)
+            .is_none_or(|result| span(result).start != old_start)
+        {
+            return None;
+        }
+        let before = scanned
+            .iter()
+            .rev()
+            .chain(self.tokens[..first].iter().rev());
+        let old_state = state(
+            self.tokens[..old].iter().rev(),
+            line_start.wrapping_add_signed(-delta),
+        );
+        (state(before, line_start) == old_state).then_some(old)
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn owned(result: Result<Token, ScannerError>) -> Lexed {
+    result
+        .map(|token| token.into_owned())
+        .map_err(|error| error.into_owned())
+}
+
+fn span(result: &Lexed) -> Span {
+    match result {
+        Ok(token) => token.span,
+        Err(error) => error.span,
+    }
+}
+
+fn line_of(result: &Lexed) -> usize {
+    match result {
+        Ok(token) => token.line,
+        Err(error) => error.line,
+    }
+}
+
+/// The `after_dot` and `code_on_line` state of a scanner at a position on the line starting at
+/// `line_start`, given the tokens before it from last to first
+fn state<'a>(mut before: impl Iterator<Item = &'a Lexed>, line_start: usize) -> (bool, bool) {
+    let last_code = before.find(|result| {
+        !matches!(
+            result.as_ref().map(OwnedToken::kind),
+            Ok(TokenKind::Whitespace(_)
+                | TokenKind::Newline
+                | TokenKind::LineComment(_)
+                | TokenKind::BlockComment(_)
+                | TokenKind::Shebang(_))
+        )
+    });
+    match last_code {
+        Some(result) => (
+            matches!(
+                result.as_ref().map(OwnedToken::kind),
+                Ok(TokenKind::Dot | TokenKind::OptionalDot)
+            ),
+            span(result).end >= line_start,
+        ),
+        None => (false, false),
+    }
+}
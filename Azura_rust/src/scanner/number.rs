@@ -0,0 +1,218 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::error::LiteralError;
+
+use super::{BigIntLiteral, TokenKind};
+
+/// The result of scanning a numeric literal
+pub(super) struct Number<'a> {
+    /// Index right after the last byte of the literal
+    pub end: usize,
+    /// Either the literal, or the error with the index of the byte it points at
+    pub value: Result<TokenKind<'a>, (LiteralError, usize)>,
+    pub radix: u32,
+}
+
+/// Scans the numeric literal starting at `start`, which has to be an ASCII digit.
+///
+/// Integers can have a `0b`, `0o` or `0x` radix prefix, and all literals may contain `_` as a
+/// digit separator. Decimal floats support exponents (`6.02e23`, `1E-9`) and hexadecimal floats
+/// use a binary exponent (`0x1.8p3`), which is required if they have a fractional part.
+///
+/// A `.` only belongs to the literal if it is followed by a digit, or if nothing that could
+/// continue an expression follows it, so `5.` is a float, while in `5.abs()` and `1..2` the dot
+/// is left for the next token. A leading dot like in `.5` is never part of a number.
+pub(super) fn scan(source: &str, start: usize) -> Number<'_> {
+    let bytes = source.as_bytes();
+    let (radix, digits_start) = match bytes.get(start + 1) {
+        Some(b'b') if bytes[start] == b'0' => (2, start + 2),
+        Some(b'o') if bytes[start] == b'0' => (8, start + 2),
+        Some(b'x') if bytes[start] == b'0' => (16, start + 2),
+        _ => (10, start),
+    };
+    let is_exponent_marker = |byte: &u8| match radix {
+        10 => matches!(byte, b'e' | b'E'),
+        16 => matches!(byte, b'p' | b'P'),
+        _ => false,
+    };
+    let is_fraction_digit = |byte: &u8| match radix {
+        16 => byte.is_ascii_hexdigit(),
+        _ => byte.is_ascii_digit(),
+    };
+    // Everything alphanumeric is taken as part of the literal, so `0o19` and `12ab` are
+    // reported as invalid digits instead of being split into several tokens
+    let digit_run = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|&byte| {
+                (byte.is_ascii_alphanumeric() || *byte == b'_') && !is_exponent_marker(byte)
+            })
+            .count()
+    };
+
+    let integer = digits_start..digit_run(digits_start);
+    let mut end = integer.end;
+
+    let mut fraction = None;
+    if bytes.get(end) == Some(&b'.') {
+        match bytes.get(end + 1) {
+            Some(next) if is_fraction_digit(next) => {
+                let fraction_end = digit_run(end + 1);
+                // Without an exponent `0x10.face` is a method call rather than a float
+                if radix != 16 || bytes.get(fraction_end).is_some_and(is_exponent_marker) {
+                    fraction = Some(end + 1..fraction_end);
+                    end = fraction_end;
+                }
+            }
+            Some(next) if next.is_ascii_alphabetic() || matches!(next, b'_' | b'.') => (),
+            _ if radix == 10 => {
+                fraction = Some(end + 1..end + 1);
+                end += 1;
+            }
+            _ => (),
+        }
+    }
+
+    let mut exponent = None;
+    if bytes.get(end).is_some_and(is_exponent_marker) {
+        let marker = end;
+        end += 1;
+        let digits_start = end;
+        if matches!(bytes.get(end), Some(b'+' | b'-')) {
+            end += 1;
+        }
+        end += bytes[end..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit() || **byte == b'_')
+            .count();
+        let digits = &source[digits_start..end];
+        // Trailing garbage, like the `x` in `1e5x`, still belongs to the literal
+        let garbage_end = digit_run(end);
+        exponent = Some(if !digits.bytes().any(|byte| byte.is_ascii_digit()) {
+            Err((LiteralError::MalformedExponent, marker))
+        } else if garbage_end > end {
+            Err((invalid_digit(bytes[end], 10), end))
+        } else {
+            Ok(strip_separators(digits))
+        });
+        end = garbage_end;
+    }
+
+    Number {
+        end,
+        value: value(source, start..end, radix, integer, fraction, exponent),
+        radix,
+    }
+}
+
+/// Scans the number right after a `.`, like the `0` in `pair.0`. It can only be a decimal
+/// integer, so `pair.0.1` accesses two fields instead of containing the float `0.1`.
+pub(super) fn scan_index(source: &str, start: usize) -> Number<'_> {
+    let end = start
+        + source.as_bytes()[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_alphanumeric() || **byte == b'_')
+            .count();
+    Number {
+        end,
+        value: value(source, start..end, 10, start..end, None, None),
+        radix: 10,
+    }
+}
+
+fn value<'a>(
+    source: &'a str,
+    literal: Range<usize>,
+    radix: u32,
+    integer: Range<usize>,
+    fraction: Option<Range<usize>>,
+    exponent: Option<Result<Cow<'_, str>, (LiteralError, usize)>>,
+) -> Result<TokenKind<'a>, (LiteralError, usize)> {
+    let start = literal.start;
+    check_digits(source, integer.clone(), radix)?;
+    if !source[integer.clone()].bytes().any(|byte| byte != b'_') {
+        return Err((LiteralError::EmptyDigits, start));
+    }
+    if let Some(fraction) = &fraction {
+        if matches!(radix, 2 | 8) {
+            return Err((LiteralError::FloatWithRadix { radix }, fraction.start - 1));
+        }
+        check_digits(source, fraction.clone(), radix)?;
+    }
+    let exponent = exponent.transpose()?;
+    let digits = &source[integer];
+    let integer = strip_separators(digits);
+    let fraction = fraction.map(|fraction| strip_separators(&source[fraction]));
+
+    let float = match (fraction, exponent) {
+        (None, None) => {
+            // Integers never overflow here, those that are too large are left for the runtime
+            return Ok(isize::from_str_radix(&integer, radix).map_or(
+                TokenKind::BigInteger(BigIntLiteral { digits, radix }),
+                TokenKind::Integer,
+            ));
+        }
+        (fraction, exponent) if radix == 16 => {
+            let fraction = fraction.unwrap_or_default();
+            // Every hexadecimal digit of the fraction moves the binary point by 4 places
+            let mantissa = integer
+                .chars()
+                .chain(fraction.chars())
+                .filter_map(|digit| digit.to_digit(16))
+                .fold(0f64, |mantissa, digit| mantissa * 16. + f64::from(digit));
+            let exponent = exponent
+                .unwrap_or_default()
+                .parse::<i32>()
+                .map_err(|_| (LiteralError::Overflow, start))?;
+            mantissa * 2f64.powi(exponent.saturating_sub(4 * fraction.len() as i32))
+        }
+        // Without separators the literal already is in a form `parse` understands
+        _ if !source[literal.clone()].contains('_') => source[literal]
+            .parse()
+            .map_err(|_| (LiteralError::MalformedExponent, start))?,
+        (fraction, exponent) => {
+            let fraction = fraction.unwrap_or_default();
+            let exponent = exponent.map_or_else(String::new, |exponent| format!("e{exponent}"));
+            format!("{integer}.{fraction}{exponent}")
+                .parse()
+                .map_err(|_| (LiteralError::MalformedExponent, start))?
+        }
+    };
+    if float.is_infinite() {
+        return Err((LiteralError::Overflow, start));
+    }
+    Ok(TokenKind::Float(float))
+}
+
+/// Finds the first character in `range` that is neither a digit of `radix` nor a separator
+fn check_digits(
+    source: &str,
+    range: Range<usize>,
+    radix: u32,
+) -> Result<(), (LiteralError, usize)> {
+    let start = range.start;
+    match source[range]
+        .bytes()
+        .enumerate()
+        .find(|(_, byte)| *byte != b'_' && !(*byte as char).is_digit(radix))
+    {
+        Some((idx, byte)) => Err((invalid_digit(byte, radix), start + idx)),
+        None => Ok(()),
+    }
+}
+
+fn invalid_digit(digit: u8, radix: u32) -> LiteralError {
+    LiteralError::InvalidDigit {
+        digit: digit as char,
+        radix,
+    }
+}
+
+/// Removes the `_` separators, only copying the digits if there are any
+fn strip_separators(digits: &str) -> Cow<'_, str> {
+    if digits.contains('_') {
+        Cow::Owned(digits.replace('_', ""))
+    } else {
+        Cow::Borrowed(digits)
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    fmt::Display,
+    io::{self, ErrorKind, Read},
+};
+
+use super::{
+    OwnedScannerError, OwnedToken, Scanner, ScannerErrorKind, StrLiteral, StrStyle, TokenKind,
+};
+
+/// How much is read from an [`io::Read`] at once
+const CHUNK_SIZE: usize = 8 * 1024;
+/// How far ahead the closing quote of a `"` string is looked for
+const MAX_STRING_LOOKAHEAD: usize = 64 * 1024;
+
+/// Scans input that arrives in chunks, like a pipe, a socket or a file too large to be read at
+/// once.
+///
+/// Only the input that was not scanned yet is kept around, which is at most the rest of the
+/// current line plus a chunk, unless a single token like a multi-line string is larger than
+/// that. Tokens are yielded as [`OwnedToken`]s, as their text might have come from several
+/// chunks.
+///
+/// A `"` string can span several lines, so whether one is unterminated depends on all of the
+/// input after it. Its closing quote is only looked for in the next 64 KiB, a longer string
+/// is reported as unterminated like one that is never closed. This is the one case where the
+/// tokens and errors differ from those of a [`Scanner`] over all of the input, which finds the
+/// closing quote however far away it is and gives back a single string token. Longer strings
+/// are best written with `"""`, which is not limited.
+pub struct StreamScanner<I> {
+    chunks: I,
+    /// Input that was read, of which everything after `scanned` was not scanned yet
+    buffer: String,
+    scanned: usize,
+    /// The bytes at the end of the last chunk that do not form a complete character yet
+    partial: Vec<u8>,
+    /// A scanner over an empty source, which keeps the position and state of the scanner in
+    /// between tokens
+    state: Scanner<'static>,
+    /// All chunks were read
+    exhausted: bool,
+    /// The end of file token or an IO error was yielded
+    finished: bool,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Scanner(OwnedScannerError),
+}
+
+/// The chunks of an [`io::Read`], as used by [`StreamScanner::from_reader`]
+pub struct ReadChunks<R>(R);
+
+/// Infallible chunks, as used by [`StreamScanner::from_chunks`]
+pub struct Chunks<I>(I);
+
+impl<R: Read> Iterator for ReadChunks<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = vec![0; CHUNK_SIZE];
+        loop {
+            return match self.0.read(&mut chunk) {
+                Ok(0) => None,
+                Ok(read) => {
+                    chunk.truncate(read);
+                    Some(Ok(chunk))
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => Some(Err(error)),
+            };
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for Chunks<I> {
+    type Item = io::Result<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(Ok)
+    }
+}
+
+impl<R: Read> StreamScanner<ReadChunks<R>> {
+    pub fn from_reader(reader: R) -> Self {
+        Self::new(ReadChunks(reader))
+    }
+}
+
+impl<I: Iterator> StreamScanner<Chunks<I>>
+where
+    I::Item: AsRef<[u8]>,
+{
+    pub fn from_chunks(chunks: impl IntoIterator<IntoIter = I>) -> Self {
+        Self::new(Chunks(chunks.into_iter()))
+    }
+}
+
+impl<I, C> StreamScanner<I>
+where
+    I: Iterator<Item = io::Result<C>>,
+    C: AsRef<[u8]>,
+{
+    pub fn new(chunks: I) -> Self {
+        StreamScanner {
+            chunks,
+            buffer: String::new(),
+            scanned: 0,
+            partial: Vec::new(),
+            state: Scanner::new(""),
+            exhausted: false,
+            finished: false,
+        }
+    }
+    /// Yields trivia as well, see [`Scanner::with_trivia`]
+    pub fn with_trivia(self) -> Self {
+        Self {
+            state: self.state.with_trivia(),
+            ..self
+        }
+    }
+
+    /// The number of bytes of input that are currently held in memory
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() + self.partial.len()
+    }
+
+    /// Adds the next chunk to the buffer, dropping everything that was scanned
+    fn fill(&mut self) -> io::Result<()> {
+        self.buffer.drain(..self.scanned);
+        self.scanned = 0;
+        let Some(chunk) = self.chunks.next().transpose()? else {
+            self.exhausted = true;
+            if !self.partial.is_empty() {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "stream did not end with a complete UTF-8 character",
+                ));
+            }
+            return Ok(());
+        };
+        self.partial.extend_from_slice(chunk.as_ref());
+        let valid = match std::str::from_utf8(&self.partial) {
+            Ok(text) => text.len(),
+            // The rest might still be completed by the next chunk
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(io::Error::new(ErrorKind::InvalidData, error)),
+        };
+        let text = std::str::from_utf8(&self.partial[..valid]).expect("checked to be valid");
+        self.buffer.push_str(text);
+        self.partial.drain(..valid);
+        Ok(())
+    }
+}
+
+/// Whether the token or error `result` might turn out differently once more input is read.
+///
+/// It ends at `end` in `buffer`, and scanning it went on up to the end of `buffer` when
+/// `to_end` is set.
+fn needs_more(
+    result: &Result<TokenKind, &ScannerErrorKind>,
+    end: usize,
+    to_end: bool,
+    buffer: &str,
+) -> bool {
+    match result {
+        Ok(TokenKind::Eof) => true,
+        // Unterminated multi-line and raw strings and block comments recover at the end of the
+        // buffer, where more input might still close them
+        Err(
+            ScannerErrorKind::Unmatched { token: Some(_) }
+            | ScannerErrorKind::UnterminatedBlockComment,
+        ) if to_end => true,
+        // A `"` string recovers at the end of its line, but might still be closed on a later one
+        Err(ScannerErrorKind::Unmatched {
+            token:
+                Some(
+                    TokenKind::Str(StrLiteral {
+                        style: StrStyle::Quoted,
+                        ..
+                    })
+                    | TokenKind::ByteStr(StrLiteral {
+                        style: StrStyle::Quoted,
+                        ..
+                    }),
+                ),
+        }) if buffer.len() < MAX_STRING_LOOKAHEAD => true,
+        // The scanner never looks past the end of a line to decide where a token ends, so
+        // everything ending before the last newline is complete
+        _ => buffer.rfind('\n').is_none_or(|newline| end > newline),
+    }
+}
+
+impl<I, C> Iterator for StreamScanner<I>
+where
+    I: Iterator<Item = io::Result<C>>,
+    C: AsRef<[u8]>,
+{
+    type Item = Result<OwnedToken, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        loop {
+            let rest = &self.buffer[self.scanned..];
+            let mut scanner = self.state.resume(rest);
+            let start = scanner.offset;
+            let result = scanner
+                .next()
+                .expect("a resumed scanner always yields a token");
+            let (kind, span) = match &result {
+                Ok(token) => (Ok(token.kind.clone()), token.span),
+                Err(error) => (Err(&error.kind), error.span),
+            };
+            let to_end = scanner.offset - start == rest.len();
+            if !self.exhausted && needs_more(&kind, span.end - start, to_end, rest) {
+                if let Err(error) = self.fill() {
+                    self.finished = true;
+                    return Some(Err(StreamError::Io(error)));
+                }
+                continue;
+            }
+            self.scanned += scanner.offset - start;
+            self.state = scanner.resume("");
+            let result = match result {
+                Ok(token) => {
+                    self.finished = token.kind == TokenKind::Eof;
+                    Ok(token.into_owned())
+                }
+                Err(error) => Err(StreamError::Scanner(error.into_owned())),
+            };
+            return Some(result);
+        }
+    }
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "Could not read input: {error}"),
+            StreamError::Scanner(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(error) => Some(error),
+            StreamError::Scanner(error) => Some(error),
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    borrow::Cow,
+    iter::{once, repeat_n},
+    ops::Range,
+};
+
+use memchr::{memchr, memchr2};
+
+use crate::error::LiteralError;
+
+use super::{StrLiteral, StrStyle, TokenKind};
+
+/// The result of scanning a string, character or byte literal
+pub(super) struct ScannedStr<'a> {
+    /// Index right after the closing delimiter, or where scanning recovers after an error
+    pub end: usize,
+    /// What the literal is called in error messages
+    pub name: &'static str,
+    pub value: Result<TokenKind<'a>, StrError<'a>>,
+}
+
+pub(super) enum StrError<'a> {
+    /// The closing delimiter is missing, `token` being everything up to the recovery point
+    Unterminated { token: Option<TokenKind<'a>> },
+    /// An escape sequence that does not stand for any character, starting at the `\` at `at`
+    InvalidEscape { sequence: &'a str, at: usize },
+    /// A literal that can not be turned into a value, `at` pointing at the offending character
+    Literal { error: LiteralError, at: usize },
+}
+
+/// The escapes a literal supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Escapes {
+    /// Any character, with `\x` escapes only up to `\x7F`
+    Text,
+    /// Only ASCII characters, with `\x` escapes for all bytes and without `\u{...}` escapes
+    Bytes,
+}
+
+/// Scans the string starting with the `"` at `start`. Three quotes in a row start a multi-line
+/// string, which only ends at the next three quotes.
+pub(super) fn scan_quoted(source: &str, start: usize) -> ScannedStr<'_> {
+    if source.as_bytes()[start..].starts_with(b"\"\"\"") {
+        let body_start = start + 3;
+        return match find_closing(source.as_bytes(), body_start, b"\"\"\"", true) {
+            Some(end) => checked(source, body_start..end, end + 3, StrStyle::Multiline, false),
+            // Everything after the opening quotes might be part of the string, so the only place
+            // to recover at is the end of input
+            None => unterminated(source, body_start..source.len(), StrStyle::Multiline, false),
+        };
+    }
+    scan_escaped(source, start + 1, false)
+}
+
+/// Scans a `"` string whose body starts at `body_start`, holding bytes if `bytes` is set
+fn scan_escaped(source: &str, body_start: usize, bytes: bool) -> ScannedStr<'_> {
+    match find_closing(source.as_bytes(), body_start, b"\"", true) {
+        Some(end) => checked(source, body_start..end, end + 1, StrStyle::Quoted, bytes),
+        // Recover at the end of the line the string was opened on, so that everything after it
+        // is scanned as code again
+        None => unterminated(
+            source,
+            body_start..line_end(source, body_start),
+            StrStyle::Quoted,
+            bytes,
+        ),
+    }
+}
+
+/// Checks whether the `r` at `start` begins a raw string rather than an identifier
+pub(super) fn is_raw(source: &str, start: usize) -> bool {
+    let bytes = source.as_bytes();
+    bytes.get(start + 1 + hashes(bytes, start)) == Some(&b'"')
+}
+
+/// Checks whether the `b` at `start` begins a byte, byte string or raw byte string literal
+/// rather than an identifier
+pub(super) fn is_byte_literal(source: &str, start: usize) -> bool {
+    match source.as_bytes().get(start + 1) {
+        Some(b'\'' | b'"') => true,
+        Some(b'r') => is_raw(source, start + 1),
+        _ => false,
+    }
+}
+
+/// The number of `#` after the `r` of a raw string
+fn hashes(bytes: &[u8], start: usize) -> usize {
+    bytes[start + 1..]
+        .iter()
+        .take_while(|byte| **byte == b'#')
+        .count()
+}
+
+/// Scans a raw string like `r"..."` or `r#"..."#` with the `r` at `start`
+pub(super) fn scan_raw(source: &str, start: usize) -> ScannedStr<'_> {
+    scan_raw_literal(source, start, false)
+}
+
+fn scan_raw_literal(source: &str, start: usize, bytes: bool) -> ScannedStr<'_> {
+    let hashes = hashes(source.as_bytes(), start);
+    let body_start = start + hashes + 2;
+    let closing: Vec<u8> = once(b'"').chain(repeat_n(b'#', hashes)).collect();
+    let style = StrStyle::Raw { hashes };
+    let Some(end) = find_closing(source.as_bytes(), body_start, &closing, false) else {
+        return unterminated(source, body_start..source.len(), style, bytes);
+    };
+    let body = &source[body_start..end];
+    let non_ascii = body.char_indices().find(|(_, ch)| !ch.is_ascii());
+    ScannedStr {
+        end: end + closing.len(),
+        name: name(style, bytes),
+        value: match non_ascii {
+            Some((at, character)) if bytes => Err(StrError::Literal {
+                error: LiteralError::NonAscii { character },
+                at: body_start + at,
+            }),
+            _ => Ok(token(StrLiteral { body, style }, bytes)),
+        },
+    }
+}
+
+/// Scans the byte literal, byte string or raw byte string with the `b` prefix at `start`
+pub(super) fn scan_byte_literal(source: &str, start: usize) -> ScannedStr<'_> {
+    match source.as_bytes()[start + 1] {
+        b'\'' => scan_char(source, start + 1, Escapes::Bytes),
+        b'"' => scan_escaped(source, start + 2, true),
+        _ => scan_raw_literal(source, start + 1, true),
+    }
+}
+
+/// Scans the character literal starting with the `'` at `start`, which has to stand for exactly
+/// one codepoint
+pub(super) fn scan_character(source: &str, start: usize) -> ScannedStr<'_> {
+    scan_char(source, start, Escapes::Text)
+}
+
+fn scan_char(source: &str, start: usize, escapes: Escapes) -> ScannedStr<'_> {
+    let name = match escapes {
+        Escapes::Text => "character literal",
+        Escapes::Bytes => "byte literal",
+    };
+    let body_start = start + 1;
+    let line_end = line_end(source, body_start);
+    // Character literals never span multiple lines
+    let Some(end) = find_closing(&source.as_bytes()[..line_end], body_start, b"'", true) else {
+        return ScannedStr {
+            end: line_end,
+            name,
+            value: Err(StrError::Unterminated { token: None }),
+        };
+    };
+    let body = &source[body_start..end];
+    let mut chars = Vec::with_capacity(1);
+    let value = match decode(body, escapes, |ch| chars.push(ch)) {
+        Err(invalid) => Err(invalid_part(body, body_start, invalid)),
+        Ok(()) => match (chars.as_slice(), escapes) {
+            ([ch], Escapes::Text) => Ok(TokenKind::Char(*ch)),
+            // Decoding only gives back characters up to `\xFF` for bytes
+            ([ch], Escapes::Bytes) => Ok(TokenKind::Byte(*ch as u8)),
+            ([], _) => Err(StrError::Literal {
+                error: LiteralError::EmptyChar,
+                at: start,
+            }),
+            (chars, _) => Err(StrError::Literal {
+                error: LiteralError::MultipleCodepoints { count: chars.len() },
+                at: start,
+            }),
+        },
+    };
+    ScannedStr {
+        end: end + 1,
+        name,
+        value,
+    }
+}
+
+/// Index of the end of the line `from` is on
+fn line_end(source: &str, from: usize) -> usize {
+    memchr(b'\n', &source.as_bytes()[from..]).map_or(source.len(), |newline| from + newline)
+}
+
+/// Finds the start of `delimiter`, skipping over escaped characters if `escapes` is set
+fn find_closing(bytes: &[u8], from: usize, delimiter: &[u8], escapes: bool) -> Option<usize> {
+    let mut idx = from;
+    loop {
+        // Only the first byte of the delimiter and backslashes can change anything
+        let rest = bytes.get(idx..)?;
+        idx += if escapes {
+            memchr2(delimiter[0], b'\\', rest)?
+        } else {
+            memchr(delimiter[0], rest)?
+        };
+        if escapes && bytes[idx] == b'\\' {
+            idx += 2;
+            continue;
+        }
+        if bytes[idx..].starts_with(delimiter) {
+            return Some(idx);
+        }
+        idx += 1;
+    }
+}
+
+fn name(style: StrStyle, bytes: bool) -> &'static str {
+    match (style, bytes) {
+        (StrStyle::Quoted, false) => "string",
+        (StrStyle::Multiline, _) => "multi-line string",
+        (StrStyle::Raw { .. }, false) => "raw string",
+        (StrStyle::Quoted, true) => "byte string",
+        (StrStyle::Raw { .. }, true) => "raw byte string",
+    }
+}
+
+fn token(literal: StrLiteral<'_>, bytes: bool) -> TokenKind<'_> {
+    if bytes {
+        TokenKind::ByteStr(literal)
+    } else {
+        TokenKind::Str(literal)
+    }
+}
+
+fn unterminated(source: &str, body: Range<usize>, style: StrStyle, bytes: bool) -> ScannedStr<'_> {
+    let literal = StrLiteral {
+        body: &source[body.clone()],
+        style,
+    };
+    ScannedStr {
+        end: body.end,
+        name: name(style, bytes),
+        value: Err(StrError::Unterminated {
+            token: Some(token(literal, bytes)),
+        }),
+    }
+}
+
+/// Creates the literal after making sure all of its escape sequences are valid
+fn checked(
+    source: &str,
+    body: Range<usize>,
+    end: usize,
+    style: StrStyle,
+    bytes: bool,
+) -> ScannedStr<'_> {
+    let text = &source[body.clone()];
+    let escapes = if bytes { Escapes::Bytes } else { Escapes::Text };
+    ScannedStr {
+        end,
+        name: name(style, bytes),
+        value: match decode(text, escapes, |_| ()) {
+            Ok(()) => Ok(token(StrLiteral { body: text, style }, bytes)),
+            Err(invalid) => Err(invalid_part(text, body.start, invalid)),
+        },
+    }
+}
+
+/// Describes the part of `text` that could not be decoded, `text` starting at `offset`
+fn invalid_part(text: &str, offset: usize, invalid: Range<usize>) -> StrError<'_> {
+    let part = &text[invalid.clone()];
+    match part.chars().next() {
+        Some(character) if character != '\\' => StrError::Literal {
+            error: LiteralError::NonAscii { character },
+            at: offset + invalid.start,
+        },
+        _ => StrError::InvalidEscape {
+            sequence: part,
+            at: offset + invalid.start,
+        },
+    }
+}
+
+/// Calls `each` with every character `text` stands for, stopping at the first escape sequence or
+/// character that is not allowed and giving back where it is in `text`
+fn decode(text: &str, escapes: Escapes, mut each: impl FnMut(char)) -> Result<(), Range<usize>> {
+    let mut idx = 0;
+    while let Some(ch) = text[idx..].chars().next() {
+        if ch == '\\' {
+            let (ch, len) = escape(&text[idx + 1..], escapes).map_err(|len| idx..idx + 1 + len)?;
+            if let Some(ch) = ch {
+                each(ch);
+            }
+            idx += 1 + len;
+        } else if escapes == Escapes::Bytes && !ch.is_ascii() {
+            return Err(idx..idx + ch.len_utf8());
+        } else {
+            each(ch);
+            idx += ch.len_utf8();
+        }
+    }
+    Ok(())
+}
+
+/// Decodes the escape sequence at the start of `rest`, which directly follows a `\`.
+///
+/// Gives back the character it stands for, or `None` for a line continuation, along with the
+/// length of the sequence. An invalid sequence gives back the length of its invalid part.
+fn escape(rest: &str, escapes: Escapes) -> Result<(Option<char>, usize), usize> {
+    let Some(first) = rest.chars().next() else {
+        return Err(0);
+    };
+    let ch = match first {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '0' => '\0',
+        '\\' => '\\',
+        '\'' => '\'',
+        '"' => '"',
+        '\n' | '\r' if rest.starts_with('\n') || rest.starts_with("\r\n") => {
+            // A line continuation skips the newline along with the indentation of the next line
+            let newline = if first == '\r' { 2 } else { 1 };
+            let next_line = &rest[newline..];
+            let indentation = next_line.len() - next_line.trim_start_matches([' ', '\t']).len();
+            return Ok((None, newline + indentation));
+        }
+        'x' => {
+            let digits = rest[1..]
+                .bytes()
+                .take(2)
+                .take_while(u8::is_ascii_hexdigit)
+                .count();
+            let max = match escapes {
+                Escapes::Text => 0x7F,
+                Escapes::Bytes => 0xFF,
+            };
+            return match u8::from_str_radix(&rest[1..1 + digits], 16) {
+                Ok(byte) if digits == 2 && byte <= max => Ok((Some(byte as char), 3)),
+                _ => Err(1 + digits),
+            };
+        }
+        'u' if escapes == Escapes::Text => {
+            let Some(braced) = rest[1..].strip_prefix('{') else {
+                return Err(1);
+            };
+            let digits = braced.bytes().take_while(u8::is_ascii_hexdigit).count();
+            if !(1..=6).contains(&digits) || braced.as_bytes().get(digits) != Some(&b'}') {
+                return Err(2 + digits);
+            }
+            let len = digits + 3;
+            return u32::from_str_radix(&braced[..digits], 16)
+                .ok()
+                .and_then(char::from_u32)
+                .map(|ch| (Some(ch), len))
+                .ok_or(len);
+        }
+        other => return Err(other.len_utf8()),
+    };
+    Ok((Some(ch), 1))
+}
+
+/// Replaces all escape sequences with the characters they stand for.
+///
+/// Invalid parts are rejected by the scanner, so decoding only stops early for literals that
+/// were created by hand.
+fn unescape(text: &str) -> Cow<'_, str> {
+    if !text.contains('\\') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let _ = decode(text, Escapes::Text, |ch| out.push(ch));
+    Cow::Owned(out)
+}
+
+/// Removes the indentation shared by all lines of a multi-line string.
+///
+/// A newline right after the opening quotes is dropped, and if the closing quotes are on a line
+/// of their own, that line is dropped too, while its indentation still counts as shared.
+fn strip_indentation(body: &str) -> String {
+    let body = body
+        .strip_prefix('\n')
+        .or_else(|| body.strip_prefix("\r\n"))
+        .unwrap_or(body);
+    let mut lines: Vec<&str> = body.split('\n').collect();
+    let closing_line = match lines.last() {
+        Some(last) if lines.len() > 1 && last.trim_start_matches([' ', '\t']).is_empty() => {
+            lines.pop()
+        }
+        _ => None,
+    };
+    let indentation = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let shared = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .chain(closing_line.as_ref())
+        .map(|line| indentation(line))
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| &line[shared.min(indentation(line))..])
+        .collect();
+    lines.join("\n")
+}
+
+impl<'a> StrLiteral<'a> {
+    /// The text a [`TokenKind::Str`] literal stands for, with its escapes and indentation
+    /// processed
+    pub fn value(&self) -> Cow<'a, str> {
+        match self.style {
+            StrStyle::Raw { .. } => Cow::Borrowed(self.body),
+            StrStyle::Quoted => unescape(self.body),
+            StrStyle::Multiline => Cow::Owned(unescape(&strip_indentation(self.body)).into_owned()),
+        }
+    }
+
+    /// The bytes a [`TokenKind::ByteStr`] literal stands for
+    pub fn bytes(&self) -> Cow<'a, [u8]> {
+        if matches!(self.style, StrStyle::Raw { .. }) || !self.body.contains('\\') {
+            return Cow::Borrowed(self.body.as_bytes());
+        }
+        let mut out = Vec::with_capacity(self.body.len());
+        // Decoding only gives back characters up to `\xFF` for bytes
+        let _ = decode(self.body, Escapes::Bytes, |ch| out.push(ch as u8));
+        Cow::Owned(out)
+    }
+}
@@ -1,5 +1,106 @@
+use std::fmt::Display;
+
 use crate::keywords::Keyword;
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    /// The exact source text of the token
+    pub lexeme: &'a str,
+    pub line: usize,
+    /// Column of the first character, starting at 1
+    pub pos: usize,
+    pub span: Span,
+}
+
+/// A [`Token`] that owns its text, so it can outlive the source it was scanned from
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedToken {
+    /// The kind with its text left empty, which is stored in `text` instead
+    kind: TokenKind<'static>,
+    text: String,
+    pub lexeme: String,
+    pub line: usize,
+    pub pos: usize,
+    pub span: Span,
+}
+
+impl<'a> Token<'a> {
+    pub fn into_owned(self) -> OwnedToken {
+        let mut text = String::new();
+        OwnedToken {
+            kind: self.kind.map_text(|borrowed| {
+                text = borrowed.to_owned();
+                ""
+            }),
+            text,
+            lexeme: self.lexeme.to_owned(),
+            line: self.line,
+            pos: self.pos,
+            span: self.span,
+        }
+    }
+}
+
+impl OwnedToken {
+    pub fn kind(&self) -> TokenKind<'_> {
+        self.kind.clone().map_text(|_| &self.text)
+    }
+    /// Borrows the token, to be used wherever a [`Token`] is expected
+    pub fn as_token(&self) -> Token<'_> {
+        Token {
+            kind: self.kind(),
+            lexeme: &self.lexeme,
+            line: self.line,
+            pos: self.pos,
+            span: self.span,
+        }
+    }
+}
+
+/// A range of byte offsets into the scanned source
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The digits of an integer literal that is too large for [`TokenKind::Integer`], without its
+/// radix prefix and possibly containing `_` separators
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BigIntLiteral<'a> {
+    pub digits: &'a str,
+    pub radix: u32,
+}
+
+/// A string or byte string literal as written in the source, its text is given by
+/// [`StrLiteral::value`] and its bytes by [`StrLiteral::bytes`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct StrLiteral<'a> {
+    /// Everything between the delimiters, with escapes and indentation left as they are
+    pub body: &'a str,
+    pub style: StrStyle,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StrStyle {
+    /// `"..."`, with escape sequences
+    Quoted,
+    /// `"""..."""`, spanning multiple lines, with escapes and the shared indentation stripped
+    Multiline,
+    /// `r"..."` or `r#"..."#` with any number of `#`, taken as is without any escapes
+    Raw { hashes: usize },
+}
+
+/// How a [`TokenKind::DocComment`] was written
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CommentStyle {
+    /// `/// ...`, running to the end of the line
+    Line,
+    /// `/** ... */`
+    Block,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenKind<'a> {
     // Single-character tokens;
@@ -7,12 +108,16 @@ pub enum TokenKind<'a> {
     CPar,
     OBrace,
     CBrace,
+    OBracket,
+    CBracket,
     Comma,
     Dot,
     Semicolon,
     Colon,
     Hashtag,
-    // One or two character tokens
+    Question,
+    BitNot, // Bitwise not, ~
+    // Multi-character tokens
     Walrus,
     Div,
     DivAssign,
@@ -42,28 +147,317 @@ pub enum TokenKind<'a> {
     LeftShiftAssign,
     RightShift, // Bitwise right-shift, >>
     RightShiftAssign,
+    Arrow,      // ->
+    FatArrow,   // =>
+    LogicalAnd, // &&
+    LogicalAndAssign,
+    LogicalOr, // ||
+    LogicalOrAssign,
+    Range,          // ..
+    RangeInclusive, // ..=
+    NullCoalesce,   // ??
+    NullCoalesceAssign,
+    OptionalDot, // ?.
+    Pow,         // Exponentiation, **
+    PowAssign,
+    // Always the last token, with an empty span at the end of input
+    Eof,
+    // Comments
+    DocComment(&'a str, CommentStyle), // Text of a `///` or `/** */` comment without the delimiters
+    // A `#` pragma as the first token on its line, i.e. `#edition 2`, with the text after the `#`
+    Pragma(&'a str),
+    // Trivia, only emitted by a scanner created with `with_trivia`
+    Whitespace(&'a str),
+    Newline,
+    LineComment(&'a str),  // Text of a `//` comment without the delimiter
+    BlockComment(&'a str), // Text of a `/* */` comment without the delimiters
+    Shebang(&'a str),      // Text of a `#!` line at the very start, without the `#!`
     // Literals
     Ident(&'a str),
-    Str(&'a str),
-    Integer(isize), // TODO: Handle overflows, arbitrary precision
-    Float(f64),     // <-/
-    Keyword(Keyword), //
-                    // // Keywords
-                    // And,
-                    // Class,
-                    // Else,
-                    // False,
-                    // For,
-                    // Func,
-                    // If,
-                    // Nil,
-                    // Or,
-                    // Info,
-                    // Return,
-                    // Super,
-                    // This,
-                    // True,
-                    // Var,
-                    // While,
-                    // Interpolation,
+    Str(StrLiteral<'a>),
+    Char(char),
+    Byte(u8),                // A `b'a'` literal
+    ByteStr(StrLiteral<'a>), // A `b"..."` or `br"..."` literal, never multi-line
+    Integer(isize),
+    BigInteger(BigIntLiteral<'a>), // An integer literal that does not fit into an `isize`
+    Float(f64),
+    Keyword(Keyword),
+}
+
+impl<'a> TokenKind<'a> {
+    /// The name of the kind, without any of its data. These are the names of the variants and
+    /// do not change, so tools can rely on them.
+    pub fn name(&self) -> &'static str {
+        use TokenKind::*;
+        match self {
+            OPar => "OPar",
+            CPar => "CPar",
+            OBrace => "OBrace",
+            CBrace => "CBrace",
+            OBracket => "OBracket",
+            CBracket => "CBracket",
+            Comma => "Comma",
+            Dot => "Dot",
+            Semicolon => "Semicolon",
+            Colon => "Colon",
+            Hashtag => "Hashtag",
+            Question => "Question",
+            BitNot => "BitNot",
+            Walrus => "Walrus",
+            Div => "Div",
+            DivAssign => "DivAssign",
+            Mul => "Mul",
+            MulAssign => "MulAssign",
+            Sub => "Sub",
+            SubAssign => "SubAssign",
+            Add => "Add",
+            AddAssign => "AddAssign",
+            Equal => "Equal",
+            NotEqual => "NotEqual",
+            Bang => "Bang",
+            Reassignment => "Reassignment",
+            Greater => "Greater",
+            GreaterOrEqual => "GreaterOrEqual",
+            Less => "Less",
+            LessOrEqual => "LessOrEqual",
+            BitOr => "BitOr",
+            BitOrAssign => "BitOrAssign",
+            BitXor => "BitXor",
+            BitXorAssign => "BitXorAssign",
+            Rem => "Rem",
+            RemAssign => "RemAssign",
+            BitAnd => "BitAnd",
+            BitAndAssign => "BitAndAssign",
+            LeftShift => "LeftShift",
+            LeftShiftAssign => "LeftShiftAssign",
+            RightShift => "RightShift",
+            RightShiftAssign => "RightShiftAssign",
+            Arrow => "Arrow",
+            FatArrow => "FatArrow",
+            LogicalAnd => "LogicalAnd",
+            LogicalAndAssign => "LogicalAndAssign",
+            LogicalOr => "LogicalOr",
+            LogicalOrAssign => "LogicalOrAssign",
+            Range => "Range",
+            RangeInclusive => "RangeInclusive",
+            NullCoalesce => "NullCoalesce",
+            NullCoalesceAssign => "NullCoalesceAssign",
+            OptionalDot => "OptionalDot",
+            Pow => "Pow",
+            PowAssign => "PowAssign",
+            Eof => "Eof",
+            DocComment(..) => "DocComment",
+            Pragma(_) => "Pragma",
+            Whitespace(_) => "Whitespace",
+            Newline => "Newline",
+            LineComment(_) => "LineComment",
+            BlockComment(_) => "BlockComment",
+            Shebang(_) => "Shebang",
+            Ident(_) => "Ident",
+            Str(_) => "Str",
+            Char(_) => "Char",
+            Byte(_) => "Byte",
+            ByteStr(_) => "ByteStr",
+            Integer(_) => "Integer",
+            BigInteger(_) => "BigInteger",
+            Float(_) => "Float",
+            Keyword(_) => "Keyword",
+        }
+    }
+    /// Replaces the text the token borrows from the source, i.e. the name of an identifier,
+    /// keeping everything else as it is
+    pub fn map_text<'b>(self, f: impl FnOnce(&'a str) -> &'b str) -> TokenKind<'b> {
+        use TokenKind::*;
+        match self {
+            OPar => OPar,
+            CPar => CPar,
+            OBrace => OBrace,
+            CBrace => CBrace,
+            OBracket => OBracket,
+            CBracket => CBracket,
+            Comma => Comma,
+            Dot => Dot,
+            Semicolon => Semicolon,
+            Colon => Colon,
+            Hashtag => Hashtag,
+            Question => Question,
+            BitNot => BitNot,
+            Walrus => Walrus,
+            Div => Div,
+            DivAssign => DivAssign,
+            Mul => Mul,
+            MulAssign => MulAssign,
+            Sub => Sub,
+            SubAssign => SubAssign,
+            Add => Add,
+            AddAssign => AddAssign,
+            Equal => Equal,
+            NotEqual => NotEqual,
+            Bang => Bang,
+            Reassignment => Reassignment,
+            Greater => Greater,
+            GreaterOrEqual => GreaterOrEqual,
+            Less => Less,
+            LessOrEqual => LessOrEqual,
+            BitOr => BitOr,
+            BitOrAssign => BitOrAssign,
+            BitXor => BitXor,
+            BitXorAssign => BitXorAssign,
+            Rem => Rem,
+            RemAssign => RemAssign,
+            BitAnd => BitAnd,
+            BitAndAssign => BitAndAssign,
+            LeftShift => LeftShift,
+            LeftShiftAssign => LeftShiftAssign,
+            RightShift => RightShift,
+            RightShiftAssign => RightShiftAssign,
+            Arrow => Arrow,
+            FatArrow => FatArrow,
+            LogicalAnd => LogicalAnd,
+            LogicalAndAssign => LogicalAndAssign,
+            LogicalOr => LogicalOr,
+            LogicalOrAssign => LogicalOrAssign,
+            Range => Range,
+            RangeInclusive => RangeInclusive,
+            NullCoalesce => NullCoalesce,
+            NullCoalesceAssign => NullCoalesceAssign,
+            OptionalDot => OptionalDot,
+            Pow => Pow,
+            PowAssign => PowAssign,
+            Eof => Eof,
+            Newline => Newline,
+            Char(ch) => Char(ch),
+            Byte(byte) => Byte(byte),
+            Integer(integer) => Integer(integer),
+            Float(float) => Float(float),
+            Keyword(keyword) => Keyword(keyword),
+            DocComment(text, style) => DocComment(f(text), style),
+            Pragma(text) => Pragma(f(text)),
+            Whitespace(text) => Whitespace(f(text)),
+            LineComment(text) => LineComment(f(text)),
+            BlockComment(text) => BlockComment(f(text)),
+            Shebang(text) => Shebang(f(text)),
+            Ident(ident) => Ident(f(ident)),
+            Str(StrLiteral { body, style }) => Str(StrLiteral {
+                body: f(body),
+                style,
+            }),
+            ByteStr(StrLiteral { body, style }) => ByteStr(StrLiteral {
+                body: f(body),
+                style,
+            }),
+            BigInteger(BigIntLiteral { digits, radix }) => BigInteger(BigIntLiteral {
+                digits: f(digits),
+                radix,
+            }),
+        }
+    }
+}
+
+/// Writes the token the way it would be spelled in source code
+impl<'a> Display for TokenKind<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TokenKind::*;
+        let text = match self {
+            OPar => "(",
+            CPar => ")",
+            OBrace => "{",
+            CBrace => "}",
+            OBracket => "[",
+            CBracket => "]",
+            Comma => ",",
+            Dot => ".",
+            Semicolon => ";",
+            Colon => ":",
+            Hashtag => "#",
+            Question => "?",
+            BitNot => "~",
+            Walrus => ":=",
+            Div => "/",
+            DivAssign => "/=",
+            Mul => "*",
+            MulAssign => "*=",
+            Sub => "-",
+            SubAssign => "-=",
+            Add => "+",
+            AddAssign => "+=",
+            Equal => "==",
+            NotEqual => "!=",
+            Bang => "!",
+            Reassignment => "=",
+            Greater => ">",
+            GreaterOrEqual => ">=",
+            Less => "<",
+            LessOrEqual => "<=",
+            BitOr => "|",
+            BitOrAssign => "|=",
+            BitXor => "^",
+            BitXorAssign => "^=",
+            Rem => "%",
+            RemAssign => "%=",
+            BitAnd => "&",
+            BitAndAssign => "&=",
+            LeftShift => "<<",
+            LeftShiftAssign => "<<=",
+            RightShift => ">>",
+            RightShiftAssign => ">>=",
+            Arrow => "->",
+            FatArrow => "=>",
+            LogicalAnd => "&&",
+            LogicalAndAssign => "&&=",
+            LogicalOr => "||",
+            LogicalOrAssign => "||=",
+            Range => "..",
+            RangeInclusive => "..=",
+            NullCoalesce => "??",
+            NullCoalesceAssign => "??=",
+            OptionalDot => "?.",
+            Pow => "**",
+            PowAssign => "**=",
+            Eof => "end of file",
+            DocComment(text, CommentStyle::Line) => return write!(f, "///{text}"),
+            DocComment(text, CommentStyle::Block) => return write!(f, "/**{text}*/"),
+            Whitespace(text) => text,
+            Newline => "\n",
+            LineComment(text) => return write!(f, "//{text}"),
+            BlockComment(text) => return write!(f, "/*{text}*/"),
+            Shebang(text) => return write!(f, "#!{text}"),
+            Pragma(text) => return write!(f, "#{text}"),
+            Ident(ident) => ident,
+            Str(StrLiteral { body, style }) => return write_str(f, "", body, *style),
+            Char(ch) => return write!(f, "'{}'", ch.escape_debug()),
+            Byte(byte) => return write!(f, "b'{}'", byte.escape_ascii()),
+            ByteStr(StrLiteral { body, style }) => return write_str(f, "b", body, *style),
+            Integer(integer) => return write!(f, "{integer}"),
+            BigInteger(BigIntLiteral { digits, radix }) => {
+                let prefix = match radix {
+                    2 => "0b",
+                    8 => "0o",
+                    16 => "0x",
+                    _ => "",
+                };
+                return write!(f, "{prefix}{digits}");
+            }
+            Float(float) => return write!(f, "{float:?}"),
+            Keyword(keyword) => return write!(f, "{keyword}"),
+        };
+        f.write_str(text)
+    }
+}
+
+/// Writes a string literal with the given prefix and the delimiters of its style
+fn write_str(
+    f: &mut std::fmt::Formatter<'_>,
+    prefix: &str,
+    body: &str,
+    style: StrStyle,
+) -> std::fmt::Result {
+    match style {
+        StrStyle::Quoted => write!(f, "{prefix}\"{body}\""),
+        StrStyle::Multiline => write!(f, "{prefix}\"\"\"{body}\"\"\""),
+        StrStyle::Raw { hashes } => {
+            let hashes = "#".repeat(hashes);
+            write!(f, "{prefix}r{hashes}\"{body}\"{hashes}")
+        }
+    }
 }
@@ -0,0 +1,114 @@
+//! Semantic classification of tokens for editors, telling variables apart by their scope.
+//!
+//! Unlike the [highlighter](crate::highlight), which only looks at single tokens, every
+//! identifier is resolved by the [`resolver`], so a global and a local of the same name get
+//! different categories and the name in `have x` is marked as a declaration.
+use std::collections::HashMap;
+
+use crate::{
+    keywords::KEYWORDS,
+    resolver::{self, Scope},
+    scanner::{Scanner, Token, TokenKind},
+};
+
+/// What a token means in the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// Keywords, `true`, `false`, `nil` and pragmas
+    Keyword,
+    GlobalVariable,
+    LocalVariable,
+    /// The categories for functions and classes, which are keywords for now but not part of the
+    /// grammar yet, so no token is classified as them
+    Parameter,
+    Function,
+    Class,
+    Field,
+    /// String, byte string, character and byte literals
+    String,
+    Number,
+    Operator,
+    /// Comments of all kinds, including doc comments and the shebang
+    Comment,
+}
+
+impl Category {
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Keyword => "keyword",
+            Category::GlobalVariable => "global variable",
+            Category::LocalVariable => "local variable",
+            Category::Parameter => "parameter",
+            Category::Function => "function",
+            Category::Class => "class",
+            Category::Field => "field",
+            Category::String => "string",
+            Category::Number => "number",
+            Category::Operator => "operator",
+            Category::Comment => "comment",
+        }
+    }
+}
+
+/// A token with its category
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticToken<'a> {
+    pub token: Token<'a>,
+    pub category: Category,
+    /// Set for the name of a variable where it is declared, i.e. the `x` in `have x := 1`
+    pub declaration: bool,
+}
+
+/// Classifies the tokens of `source`, in order. Whitespace, punctuation and input the scanner
+/// could not make sense of have no category and are left out.
+pub fn classify(source: &str) -> Vec<SemanticToken<'_>> {
+    let resolution = resolver::resolve(source);
+    // The scope of every identifier by where it starts, and whether it is a declaration
+    let scopes: HashMap<usize, (Scope, bool)> = resolution
+        .declarations
+        .iter()
+        .map(|found| (found.token.span.start, (found.scope, true)))
+        .chain(
+            resolution
+                .references
+                .iter()
+                .map(|found| (found.token.span.start, (found.scope, false))),
+        )
+        .collect();
+
+    let mut tokens = Vec::new();
+    for token in Scanner::new(source)
+        .with_trivia()
+        .with_keywords(&KEYWORDS)
+        .flatten()
+    {
+        use TokenKind::*;
+        let mut declaration = false;
+        let category = match &token.kind {
+            Keyword(_) | Pragma(_) => Category::Keyword,
+            Ident(_) => {
+                let (scope, declared) = scopes
+                    .get(&token.span.start)
+                    .copied()
+                    .unwrap_or((Scope::Global, false));
+                declaration = declared;
+                match scope {
+                    Scope::Global => Category::GlobalVariable,
+                    Scope::Local { .. } => Category::LocalVariable,
+                }
+            }
+            Str(_) | ByteStr(_) | Char(_) | Byte(_) => Category::String,
+            Integer(_) | BigInteger(_) | Float(_) => Category::Number,
+            LineComment(_) | BlockComment(_) | DocComment(..) | Shebang(_) => Category::Comment,
+            OPar | CPar | OBrace | CBrace | OBracket | CBracket | Comma | Dot | Semicolon
+            | Colon | Whitespace(_) | Newline | Eof => continue,
+            _ => Category::Operator,
+        };
+        tokens.push(SemanticToken {
+            token,
+            category,
+            declaration,
+        });
+    }
+    tokens
+}
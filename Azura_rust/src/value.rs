@@ -0,0 +1,101 @@
+use std::{fmt::Display, rc::Rc};
+
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+
+use crate::scanner::BigIntLiteral;
+
+mod arithmetic;
+
+pub use self::arithmetic::OperationError;
+
+/// A value as seen by running Azura code.
+///
+/// Integers that overflow are promoted into [`Value::BigInt`] when the `bigint` feature is
+/// enabled, and raise [`OperationError::Overflow`] otherwise. A `BigInt` is always outside the
+/// range of an `isize`, results that fit are turned back into a [`Value::Int`].
+///
+/// A [`Value::Byte`] takes part in arithmetic as the integer it stands for, so the results of
+/// arithmetic on bytes are always ints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Int(isize),
+    #[cfg(feature = "bigint")]
+    BigInt(Rc<BigInt>),
+    Float(f64),
+    Str(Rc<str>),
+    Char(char),
+    Byte(u8),
+    Bytes(Rc<[u8]>),
+}
+
+impl Value {
+    /// Creates the value of an integer literal that did not fit into an `isize`
+    pub fn from_big_literal(literal: BigIntLiteral) -> Result<Value, OperationError> {
+        #[cfg(feature = "bigint")]
+        {
+            let digits: String = literal.digits.chars().filter(|&ch| ch != '_').collect();
+            BigInt::parse_bytes(digits.as_bytes(), literal.radix)
+                .map(Value::from)
+                .ok_or(OperationError::Overflow)
+        }
+        #[cfg(not(feature = "bigint"))]
+        {
+            let _ = literal;
+            Err(OperationError::Overflow)
+        }
+    }
+
+    /// The name of the value's type, as shown in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+            Value::Bool(_) => "bool",
+            Value::Int(_) => "int",
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => "int",
+            Value::Float(_) => "float",
+            Value::Str(_) => "string",
+            Value::Char(_) => "char",
+            Value::Byte(_) => "byte",
+            Value::Bytes(_) => "bytes",
+        }
+    }
+
+    /// Only `nil` and `false` are falsy
+    pub fn is_falsy(&self) -> bool {
+        matches!(self, Value::Nil | Value::Bool(false))
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Self {
+        use num_traits::ToPrimitive;
+        match value.to_isize() {
+            Some(small) => Value::Int(small),
+            None => Value::BigInt(Rc::new(value)),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Nil => f.write_str("nil"),
+            Value::Bool(bool) => write!(f, "{bool}"),
+            Value::Int(int) => write!(f, "{int}"),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(int) => write!(f, "{int}"),
+            // Debug formatting keeps the `.0`, so floats can be told apart from integers
+            Value::Float(float) => write!(f, "{float:?}"),
+            Value::Str(str) => f.write_str(str),
+            Value::Char(ch) => write!(f, "{ch}"),
+            Value::Byte(byte) => write!(f, "{byte}"),
+            // Written the way a byte string literal is, as the bytes might not be valid UTF-8
+            Value::Bytes(bytes) => write!(f, "b\"{}\"", bytes.escape_ascii()),
+        }
+    }
+}
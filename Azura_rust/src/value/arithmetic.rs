@@ -0,0 +1,375 @@
+use std::{cmp::Ordering, fmt::Display, rc::Rc};
+
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "bigint")]
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use super::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperationError {
+    /// An integer result that does not fit into an `isize`, only raised without the `bigint`
+    /// feature
+    Overflow,
+    DivisionByZero,
+    NegativeShift,
+    InvalidOperands {
+        operation: &'static str,
+        left: &'static str,
+        right: &'static str,
+    },
+    InvalidOperand {
+        operation: &'static str,
+        operand: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitAnd,
+    BitOr,
+    BitXor,
+}
+
+impl Operation {
+    fn symbol(self) -> &'static str {
+        match self {
+            Operation::Add => "+",
+            Operation::Sub => "-",
+            Operation::Mul => "*",
+            Operation::Div => "/",
+            Operation::Rem => "%",
+            Operation::BitAnd => "&",
+            Operation::BitOr => "|",
+            Operation::BitXor => "^",
+        }
+    }
+    fn int(self, a: isize, b: isize) -> Option<isize> {
+        match self {
+            Operation::Add => a.checked_add(b),
+            Operation::Sub => a.checked_sub(b),
+            Operation::Mul => a.checked_mul(b),
+            Operation::Div => a.checked_div(b),
+            // Only `isize::MIN % -1` overflows, and its remainder is 0 all the same
+            Operation::Rem => Some(a.wrapping_rem(b)),
+            Operation::BitAnd => Some(a & b),
+            Operation::BitOr => Some(a | b),
+            Operation::BitXor => Some(a ^ b),
+        }
+    }
+    /// `None` for the bitwise operations, which are only defined for integers
+    fn float(self, a: f64, b: f64) -> Option<f64> {
+        match self {
+            Operation::Add => Some(a + b),
+            Operation::Sub => Some(a - b),
+            Operation::Mul => Some(a * b),
+            Operation::Div => Some(a / b),
+            Operation::Rem => Some(a % b),
+            Operation::BitAnd | Operation::BitOr | Operation::BitXor => None,
+        }
+    }
+    #[cfg(feature = "bigint")]
+    fn big(self, a: &BigInt, b: &BigInt) -> BigInt {
+        match self {
+            Operation::Add => a + b,
+            Operation::Sub => a - b,
+            Operation::Mul => a * b,
+            Operation::Div => a / b,
+            Operation::Rem => a % b,
+            Operation::BitAnd => a & b,
+            Operation::BitOr => a | b,
+            Operation::BitXor => a ^ b,
+        }
+    }
+
+    fn apply(self, lhs: &Value, rhs: &Value) -> Result<Value, OperationError> {
+        let invalid = || OperationError::InvalidOperands {
+            operation: self.symbol(),
+            left: lhs.type_name(),
+            right: rhs.type_name(),
+        };
+        let divides = matches!(self, Operation::Div | Operation::Rem);
+        match Numbers::promote(lhs, rhs).ok_or_else(invalid)? {
+            Numbers::Int(_, 0) if divides => Err(OperationError::DivisionByZero),
+            Numbers::Int(a, b) => match self.int(a, b) {
+                Some(result) => Ok(Value::Int(result)),
+                #[cfg(feature = "bigint")]
+                None => Ok(Value::from(self.big(&a.into(), &b.into()))),
+                #[cfg(not(feature = "bigint"))]
+                None => Err(OperationError::Overflow),
+            },
+            #[cfg(feature = "bigint")]
+            Numbers::Big(_, b) if divides && b.is_zero() => Err(OperationError::DivisionByZero),
+            #[cfg(feature = "bigint")]
+            Numbers::Big(a, b) => Ok(Value::from(self.big(&a, &b))),
+            Numbers::Float(a, b) => self.float(a, b).map(Value::Float).ok_or_else(invalid),
+        }
+    }
+}
+
+/// Numeric operands converted to a common type.
+///
+/// An int mixed with a float becomes a float, and an int mixed with a bigint becomes a bigint.
+/// Bytes become ints.
+enum Numbers {
+    Int(isize, isize),
+    #[cfg(feature = "bigint")]
+    Big(Rc<BigInt>, Rc<BigInt>),
+    Float(f64, f64),
+}
+
+impl Numbers {
+    fn promote(lhs: &Value, rhs: &Value) -> Option<Self> {
+        use Value::*;
+        Some(match (lhs, rhs) {
+            (Int(a), Int(b)) => Numbers::Int(*a, *b),
+            (Int(a), Float(b)) => Numbers::Float(*a as f64, *b),
+            (Float(a), Int(b)) => Numbers::Float(*a, *b as f64),
+            (Float(a), Float(b)) => Numbers::Float(*a, *b),
+            (Byte(a), _) => return Self::promote(&Int(*a as isize), rhs),
+            (_, Byte(b)) => return Self::promote(lhs, &Int(*b as isize)),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), BigInt(b)) => Numbers::Big(a.clone(), b.clone()),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Int(b)) => Numbers::Big(a.clone(), Rc::new((*b).into())),
+            #[cfg(feature = "bigint")]
+            (Int(a), BigInt(b)) => Numbers::Big(Rc::new((*a).into()), b.clone()),
+            #[cfg(feature = "bigint")]
+            (BigInt(a), Float(b)) => Numbers::Float(big_to_float(a), *b),
+            #[cfg(feature = "bigint")]
+            (Float(a), BigInt(b)) => Numbers::Float(*a, big_to_float(b)),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shift {
+    Left,
+    Right,
+}
+
+#[cfg(feature = "bigint")]
+fn big_to_float(value: &BigInt) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+impl Value {
+    /// Adds two numbers, concatenates strings and chars, or concatenates two byte strings
+    pub fn add(&self, rhs: &Value) -> Result<Value, OperationError> {
+        match (self, rhs) {
+            (Value::Str(_), Value::Str(_) | Value::Char(_)) | (Value::Char(_), Value::Str(_)) => {
+                Ok(Value::Str(Rc::from(format!("{self}{rhs}"))))
+            }
+            (Value::Bytes(a), Value::Bytes(b)) => {
+                Ok(Value::Bytes(a.iter().chain(b.iter()).copied().collect()))
+            }
+            _ => Operation::Add.apply(self, rhs),
+        }
+    }
+    pub fn sub(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Operation::Sub.apply(self, rhs)
+    }
+    pub fn mul(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Operation::Mul.apply(self, rhs)
+    }
+    /// Divides two numbers, integer division truncates towards zero
+    pub fn div(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Operation::Div.apply(self, rhs)
+    }
+    /// The remainder of a truncating division, so it has the same sign as `self`
+    pub fn rem(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Operation::Rem.apply(self, rhs)
+    }
+    pub fn bit_and(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Operation::BitAnd.apply(self, rhs)
+    }
+    pub fn bit_or(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Operation::BitOr.apply(self, rhs)
+    }
+    pub fn bit_xor(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Operation::BitXor.apply(self, rhs)
+    }
+    /// Shifts an integer left, bits shifted past the most significant one count as an overflow
+    pub fn shl(&self, rhs: &Value) -> Result<Value, OperationError> {
+        self.shift(Shift::Left, rhs)
+    }
+    /// Arithmetic right shift, which keeps the sign of `self`
+    pub fn shr(&self, rhs: &Value) -> Result<Value, OperationError> {
+        self.shift(Shift::Right, rhs)
+    }
+    fn shift(&self, direction: Shift, rhs: &Value) -> Result<Value, OperationError> {
+        let invalid = || OperationError::InvalidOperands {
+            operation: match direction {
+                Shift::Left => "<<",
+                Shift::Right => ">>",
+            },
+            left: self.type_name(),
+            right: rhs.type_name(),
+        };
+        // `None` for amounts too large for any shift to be representable
+        let amount = match rhs {
+            Value::Int(amount) if *amount < 0 => return Err(OperationError::NegativeShift),
+            Value::Int(amount) => u32::try_from(*amount).ok(),
+            Value::Byte(amount) => Some(u32::from(*amount)),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(amount) if amount.is_negative() => {
+                return Err(OperationError::NegativeShift)
+            }
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => None,
+            _ => return Err(invalid()),
+        };
+        match (self, direction) {
+            (Value::Byte(byte), _) => Value::Int(*byte as isize).shift(direction, rhs),
+            (Value::Int(0), _) => Ok(Value::Int(0)),
+            (Value::Int(int), Shift::Right) => Ok(Value::Int(
+                int >> amount.unwrap_or(u32::MAX).min(isize::BITS - 1),
+            )),
+            (Value::Int(int), Shift::Left) => {
+                let shifted = amount
+                    .filter(|amount| *amount < isize::BITS)
+                    .map(|amount| (int << amount, amount))
+                    .filter(|(shifted, amount)| shifted >> amount == *int);
+                match (shifted, amount) {
+                    (Some((shifted, _)), _) => Ok(Value::Int(shifted)),
+                    #[cfg(feature = "bigint")]
+                    (None, Some(amount)) => Ok(Value::from(BigInt::from(*int) << amount)),
+                    _ => Err(OperationError::Overflow),
+                }
+            }
+            #[cfg(feature = "bigint")]
+            (Value::BigInt(int), Shift::Left) => match amount {
+                Some(amount) => Ok(Value::from(int.as_ref() << amount)),
+                None => Err(OperationError::Overflow),
+            },
+            #[cfg(feature = "bigint")]
+            (Value::BigInt(int), Shift::Right) => match amount {
+                Some(amount) => Ok(Value::from(int.as_ref() >> amount)),
+                None => Ok(Value::Int(if int.is_negative() { -1 } else { 0 })),
+            },
+            _ => Err(invalid()),
+        }
+    }
+    pub fn neg(&self) -> Result<Value, OperationError> {
+        match self {
+            Value::Int(int) => match int.checked_neg() {
+                Some(negated) => Ok(Value::Int(negated)),
+                #[cfg(feature = "bigint")]
+                None => Ok(Value::from(-BigInt::from(*int))),
+                #[cfg(not(feature = "bigint"))]
+                None => Err(OperationError::Overflow),
+            },
+            #[cfg(feature = "bigint")]
+            Value::BigInt(int) => Ok(Value::from(-int.as_ref().clone())),
+            Value::Float(float) => Ok(Value::Float(-float)),
+            Value::Byte(byte) => Ok(Value::Int(-(*byte as isize))),
+            other => Err(OperationError::InvalidOperand {
+                operation: "-",
+                operand: other.type_name(),
+            }),
+        }
+    }
+
+    /// Orders two numbers or two chars, `None` if one of them is NaN
+    pub fn compare(&self, rhs: &Value) -> Result<Option<Ordering>, OperationError> {
+        self.compare_for("compare", rhs)
+    }
+    fn compare_for(
+        &self,
+        operation: &'static str,
+        rhs: &Value,
+    ) -> Result<Option<Ordering>, OperationError> {
+        if let (Value::Char(a), Value::Char(b)) = (self, rhs) {
+            return Ok(Some(a.cmp(b)));
+        }
+        match Numbers::promote(self, rhs) {
+            Some(Numbers::Int(a, b)) => Ok(Some(a.cmp(&b))),
+            #[cfg(feature = "bigint")]
+            Some(Numbers::Big(a, b)) => Ok(Some(a.cmp(&b))),
+            Some(Numbers::Float(a, b)) => Ok(a.partial_cmp(&b)),
+            None => Err(OperationError::InvalidOperands {
+                operation,
+                left: self.type_name(),
+                right: rhs.type_name(),
+            }),
+        }
+    }
+    pub fn less(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Ok(Value::Bool(
+            self.compare_for("<", rhs)? == Some(Ordering::Less),
+        ))
+    }
+    pub fn greater(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Ok(Value::Bool(
+            self.compare_for(">", rhs)? == Some(Ordering::Greater),
+        ))
+    }
+    pub fn less_or_equal(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Ok(Value::Bool(matches!(
+            self.compare_for("<=", rhs)?,
+            Some(Ordering::Less | Ordering::Equal)
+        )))
+    }
+    pub fn greater_or_equal(&self, rhs: &Value) -> Result<Value, OperationError> {
+        Ok(Value::Bool(matches!(
+            self.compare_for(">=", rhs)?,
+            Some(Ordering::Greater | Ordering::Equal)
+        )))
+    }
+    /// Equality as defined by the language, where numbers compare by value across types
+    pub fn equals(&self, rhs: &Value) -> bool {
+        match Numbers::promote(self, rhs) {
+            Some(_) => self.compare(rhs) == Ok(Some(Ordering::Equal)),
+            None => self == rhs,
+        }
+    }
+}
+
+impl Display for OperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationError::Overflow => f.write_str("Integer overflow"),
+            OperationError::DivisionByZero => f.write_str("Division by zero"),
+            OperationError::NegativeShift => f.write_str("Shift amount must not be negative"),
+            OperationError::InvalidOperands {
+                operation: "+",
+                left,
+                right,
+            } => write!(
+                f,
+                "Operands of `+` must be two numbers or two strings, got {left} and {right}"
+            ),
+            OperationError::InvalidOperands {
+                operation: operation @ ("&" | "|" | "^" | "<<" | ">>"),
+                left,
+                right,
+            } => write!(
+                f,
+                "Operands of `{operation}` must be integers, got {left} and {right}"
+            ),
+            OperationError::InvalidOperands {
+                operation,
+                left,
+                right,
+            } => write!(
+                f,
+                "Operands of `{operation}` must be numbers, got {left} and {right}"
+            ),
+            OperationError::InvalidOperand { operation, operand } => {
+                write!(
+                    f,
+                    "Operand of `{operation}` must be a number, got {operand}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for OperationError {}
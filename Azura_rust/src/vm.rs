@@ -0,0 +1,142 @@
+use std::{collections::HashMap, io::Write, rc::Rc};
+
+use crate::{
+    chunk::{Chunk, OpCode},
+    compiler::compile,
+    value::{OperationError, Value},
+};
+
+pub use crate::error::{InterpretError, RuntimeError};
+
+/// Runs compiled chunks, writing the output of `info` statements to `out`.
+///
+/// Globals are kept between calls to [`Vm::interpret`], so a REPL can define a variable on one
+/// line and use it on the next.
+pub struct Vm<W> {
+    stack: Vec<Value>,
+    globals: HashMap<Rc<str>, Value>,
+    out: W,
+}
+
+impl<W: Write> Vm<W> {
+    pub fn new(out: W) -> Self {
+        Vm {
+            stack: Vec::new(),
+            globals: HashMap::new(),
+            out,
+        }
+    }
+
+    /// Gives back the writer the output went to
+    pub fn into_output(self) -> W {
+        self.out
+    }
+
+    pub fn interpret(&mut self, source: &str) -> Result<(), InterpretError> {
+        let chunk = compile(source).map_err(InterpretError::Compile)?;
+        let result = self.run(&chunk);
+        // A runtime error leaves the values it was working with behind
+        self.stack.clear();
+        result.map_err(InterpretError::Runtime)
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack
+            .pop()
+            .expect("the compiler never pops an empty stack")
+    }
+    fn peek(&self) -> &Value {
+        self.stack
+            .last()
+            .expect("the compiler never peeks an empty stack")
+    }
+    fn binary(
+        &mut self,
+        operation: fn(&Value, &Value) -> Result<Value, OperationError>,
+    ) -> Result<(), OperationError> {
+        let b = self.pop();
+        let a = self.pop();
+        self.stack.push(operation(&a, &b)?);
+        Ok(())
+    }
+
+    fn run(&mut self, chunk: &Chunk) -> Result<(), RuntimeError> {
+        let name = |constant: usize| match &chunk.constants[constant] {
+            Value::Str(name) => name.clone(),
+            other => unreachable!("global names are strings, got {other:?}"),
+        };
+        for (ip, op) in chunk.code.iter().enumerate() {
+            let error = |message: String| RuntimeError {
+                message,
+                line: chunk.lines[ip],
+            };
+            let failed = |operation: OperationError| error(operation.to_string());
+            match *op {
+                OpCode::Constant(constant) => self.stack.push(chunk.constants[constant].clone()),
+                OpCode::Nil => self.stack.push(Value::Nil),
+                OpCode::True => self.stack.push(Value::Bool(true)),
+                OpCode::False => self.stack.push(Value::Bool(false)),
+                OpCode::Pop => {
+                    self.pop();
+                }
+                OpCode::GetLocal(slot) => self.stack.push(self.stack[slot].clone()),
+                OpCode::SetLocal(slot) => self.stack[slot] = self.peek().clone(),
+                OpCode::GetGlobal(constant) => {
+                    let name = name(constant);
+                    let Some(value) = self.globals.get(&name) else {
+                        return Err(error(format!("Undefined variable '{name}'.")));
+                    };
+                    self.stack.push(value.clone());
+                }
+                OpCode::SetGlobal(constant) => {
+                    let name = name(constant);
+                    let value = self.peek().clone();
+                    let Some(global) = self.globals.get_mut(&name) else {
+                        return Err(error(format!(
+                            "Variable '{name}' is undefined! \nTry doing something like \
+                             'have [your variable name] := 0'. Happy coding!"
+                        )));
+                    };
+                    *global = value;
+                }
+                OpCode::DefineGlobal(constant) => {
+                    let value = self.pop();
+                    self.globals.insert(name(constant), value);
+                }
+                OpCode::Equal => {
+                    let b = self.pop();
+                    let a = self.pop();
+                    self.stack.push(Value::Bool(a.equals(&b)));
+                }
+                OpCode::Greater => self.binary(Value::greater).map_err(failed)?,
+                OpCode::GreaterOrEqual => self.binary(Value::greater_or_equal).map_err(failed)?,
+                OpCode::Less => self.binary(Value::less).map_err(failed)?,
+                OpCode::LessOrEqual => self.binary(Value::less_or_equal).map_err(failed)?,
+                OpCode::Add => self.binary(Value::add).map_err(failed)?,
+                OpCode::Subtract => self.binary(Value::sub).map_err(failed)?,
+                OpCode::Multiply => self.binary(Value::mul).map_err(failed)?,
+                OpCode::Divide => self.binary(Value::div).map_err(failed)?,
+                OpCode::Remainder => self.binary(Value::rem).map_err(failed)?,
+                OpCode::BitAnd => self.binary(Value::bit_and).map_err(failed)?,
+                OpCode::BitOr => self.binary(Value::bit_or).map_err(failed)?,
+                OpCode::BitXor => self.binary(Value::bit_xor).map_err(failed)?,
+                OpCode::LeftShift => self.binary(Value::shl).map_err(failed)?,
+                OpCode::RightShift => self.binary(Value::shr).map_err(failed)?,
+                OpCode::Not => {
+                    let value = self.pop();
+                    self.stack.push(Value::Bool(value.is_falsy()));
+                }
+                OpCode::Negate => {
+                    let value = self.pop().neg().map_err(failed)?;
+                    self.stack.push(value);
+                }
+                OpCode::Info => {
+                    let value = self.pop();
+                    let _ = writeln!(self.out, "{value}");
+                }
+                OpCode::Return => return Ok(()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use azura::{
+    error::LiteralError,
+    scanner::{Scanner, ScannerErrorKind, StrStyle, TokenKind},
+};
+
+fn kind(source: &str) -> TokenKind<'_> {
+    let mut tokens = Scanner::new(source).map(Result::unwrap);
+    let kind = tokens.next().unwrap().kind;
+    assert_eq!(tokens.next().unwrap().kind, TokenKind::Eof, "{source:?}");
+    kind
+}
+
+fn bytes(source: &str) -> Vec<u8> {
+    match kind(source) {
+        TokenKind::ByteStr(string) => string.bytes().into_owned(),
+        other => panic!("expected a byte string, got {other:?}"),
+    }
+}
+
+#[test]
+fn chars() {
+    assert_eq!(kind("'a'"), TokenKind::Char('a'));
+    assert_eq!(kind("'é'"), TokenKind::Char('é'));
+    assert_eq!(kind("'😀'"), TokenKind::Char('😀'));
+    assert_eq!(kind(r"'\n'"), TokenKind::Char('\n'));
+    assert_eq!(kind(r"'\''"), TokenKind::Char('\''));
+    assert_eq!(kind("'\"'"), TokenKind::Char('"'));
+    assert_eq!(kind(r"'\x7F'"), TokenKind::Char('\x7F'));
+    assert_eq!(kind(r"'\u{1F600}'"), TokenKind::Char('😀'));
+}
+
+#[test]
+fn bytes_literals() {
+    assert_eq!(kind("b'a'"), TokenKind::Byte(b'a'));
+    assert_eq!(kind(r"b'\xFF'"), TokenKind::Byte(0xFF));
+    assert_eq!(kind(r"b'\0'"), TokenKind::Byte(0));
+    assert_eq!(bytes(r#"b"GET \x00\xff\n""#), b"GET \x00\xff\n");
+    assert_eq!(bytes(r#"br"\x00""#), br"\x00");
+    assert_eq!(bytes(r##"br#"say "hi""#"##), br#"say "hi""#);
+    let TokenKind::ByteStr(string) = kind(r##"br#"a"#"##) else {
+        panic!("expected a byte string");
+    };
+    assert_eq!(string.style, StrStyle::Raw { hashes: 1 });
+}
+
+#[test]
+fn b_is_still_an_identifier() {
+    let kinds: Vec<_> = Scanner::new("b br bx")
+        .map(|token| token.unwrap().kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::Ident("b"),
+            TokenKind::Ident("br"),
+            TokenKind::Ident("bx"),
+            TokenKind::Eof
+        ]
+    );
+}
+
+#[test]
+fn display_round_trips() {
+    for source in [
+        "'a'",
+        r"'\n'",
+        r"'\''",
+        "b'a'",
+        r"b'\xff'",
+        r#"b"a\n""#,
+        r##"br#"a"#"##,
+    ] {
+        assert_eq!(kind(source).to_string(), source);
+    }
+}
+
+#[test]
+fn invalid_literals() {
+    for (source, literal, pos) in [
+        ("''", LiteralError::EmptyChar, 1),
+        ("'ab'", LiteralError::MultipleCodepoints { count: 2 }, 1),
+        (
+            "'e\u{301}'",
+            LiteralError::MultipleCodepoints { count: 2 },
+            1,
+        ),
+        ("b'ab'", LiteralError::MultipleCodepoints { count: 2 }, 2),
+        ("b'é'", LiteralError::NonAscii { character: 'é' }, 3),
+        (
+            "b\"caf\u{e9}\"",
+            LiteralError::NonAscii { character: 'é' },
+            6,
+        ),
+        ("br\"\u{e9}\"", LiteralError::NonAscii { character: 'é' }, 4),
+    ] {
+        let error = Scanner::new(source)
+            .find_map(Result::err)
+            .unwrap_or_else(|| panic!("{source:?} should not scan"));
+        assert_eq!(
+            error.kind,
+            ScannerErrorKind::IncorrectLiteral { error: literal },
+            "{source:?}"
+        );
+        assert_eq!(error.pos, pos, "{source:?}");
+        assert_eq!(error.lexeme, source);
+    }
+}
+
+#[test]
+fn invalid_escapes() {
+    for (source, sequence) in [
+        (r"'\x80'", r"\x80"),
+        (r"'\x4'", r"\x4"),
+        (r"b'\u{41}'", r"\u"),
+        (r#""\xFF""#, r"\xFF"),
+    ] {
+        let error = Scanner::new(source)
+            .find_map(Result::err)
+            .unwrap_or_else(|| panic!("{source:?} should not scan"));
+        assert_eq!(
+            error.kind,
+            ScannerErrorKind::InvalidEscape { sequence },
+            "{source:?}"
+        );
+    }
+}
+
+#[test]
+fn unterminated_chars() {
+    let mut scanner = Scanner::new("x := 'a\ny");
+    let error = scanner.find_map(Result::err).unwrap();
+    assert_eq!(
+        error.message.as_deref(),
+        Some("Unterminated character literal")
+    );
+    // Character literals never continue on the next line
+    let next = scanner.next().unwrap().unwrap();
+    assert_eq!((next.kind, next.line), (TokenKind::Ident("y"), 2));
+
+    let error = Scanner::new("b'").find_map(Result::err).unwrap();
+    assert_eq!(error.message.as_deref(), Some("Unterminated byte literal"));
+    let error = Scanner::new("b\"a").find_map(Result::err).unwrap();
+    assert_eq!(error.message.as_deref(), Some("Unterminated byte string"));
+}
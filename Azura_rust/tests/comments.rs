@@ -0,0 +1,141 @@
+use azura::scanner::{CommentStyle, Scanner, ScannerErrorKind, TokenKind};
+
+mod common;
+
+use common::tokens;
+
+fn trivia(source: &str) -> Vec<TokenKind<'_>> {
+    Scanner::new(source)
+        .with_trivia()
+        .map(|token| token.unwrap().kind)
+        .collect()
+}
+
+#[test]
+fn block_comments() {
+    for (source, body) in [
+        ("/**/", ""),
+        ("/* a * b / c */", " a * b / c "),
+        ("/* ends in stars **/", " ends in stars *"),
+        (
+            "/* /* nested */ still in it */",
+            " /* nested */ still in it ",
+        ),
+        (
+            "/*/ not closed by its own slash */",
+            "/ not closed by its own slash ",
+        ),
+        ("/* a /*/ b */ c */", " a /*/ b */ c "),
+    ] {
+        assert_eq!(
+            trivia(source),
+            [TokenKind::BlockComment(body), TokenKind::Eof],
+            "{source:?}"
+        );
+    }
+}
+
+#[test]
+fn doc_comments() {
+    let kinds: Vec<_> = tokens("/// line\n//// not a doc\n/** block */ /*** not a doc */")
+        .into_iter()
+        .map(|token| token.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::DocComment(" line", CommentStyle::Line),
+            TokenKind::DocComment(" block ", CommentStyle::Block),
+            TokenKind::Eof
+        ]
+    );
+}
+
+#[test]
+fn empty_and_starred_block_comments_are_not_doc_comments() {
+    for (source, body) in [
+        ("/**/", ""),
+        ("/***/", "*"),
+        ("/*** stars */", "** stars "),
+        ("/****/", "**"),
+    ] {
+        assert_eq!(
+            trivia(source),
+            [TokenKind::BlockComment(body), TokenKind::Eof],
+            "{source:?}"
+        );
+    }
+    assert_eq!(
+        trivia("/** /* nested */ doc */"),
+        [
+            TokenKind::DocComment(" /* nested */ doc ", CommentStyle::Block),
+            TokenKind::Eof
+        ]
+    );
+}
+
+#[test]
+fn unterminated_nested_comments() {
+    for source in [
+        "/* /* */",
+        "/* /* /* */ */",
+        "/** /* */",
+        "x /* a /* b\n*/\n",
+    ] {
+        let mut scanner = Scanner::new(source);
+        let error = scanner.find_map(Result::err).unwrap();
+        assert_eq!(error.kind, ScannerErrorKind::UnterminatedBlockComment);
+        assert_eq!(error.lexeme, &source[source.find("/*").unwrap()..]);
+        assert_eq!(error.span.end, source.len(), "{source:?}");
+        assert_eq!(scanner.next().unwrap().unwrap().kind, TokenKind::Eof);
+    }
+}
+
+#[test]
+fn doc_comments_are_written_back_in_their_style() {
+    for source in ["/// line", "/** block */", "/**\n * over lines\n */"] {
+        let token = tokens(source).remove(0);
+        assert_eq!(token.kind.to_string(), source);
+        assert_eq!(token.lexeme, source);
+    }
+}
+
+#[test]
+fn lines_after_comments() {
+    let source = "// one\n/* two\nthree\n */ x\n\n  /* /*\n */ */ y // z";
+    let tokens = tokens(source);
+    let found: Vec<_> = tokens
+        .iter()
+        .map(|token| (token.kind.clone(), token.line, token.pos))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (TokenKind::Ident("x"), 4, 5),
+            (TokenKind::Ident("y"), 7, 8),
+            (TokenKind::Eof, 7, 14),
+        ]
+    );
+    assert_eq!(tokens[2].span.start, source.len());
+}
+
+#[test]
+fn unterminated_block_comments() {
+    for source in ["/* open", "/* /* nested */", "x /* a\n*/ /*\n"] {
+        let error = Scanner::new(source).find_map(Result::err).unwrap();
+        assert_eq!(error.kind, ScannerErrorKind::UnterminatedBlockComment);
+        assert_eq!(error.span.end, source.len(), "{source:?}");
+    }
+}
+
+#[test]
+fn escaped_quotes_and_backslashes() {
+    let strings: Vec<_> = tokens(r#""a\"b" "c\\" "\\\"" d"#)
+        .into_iter()
+        .filter_map(|token| match token.kind {
+            TokenKind::Str(string) => Some(string.value().into_owned()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(strings, ["a\"b", "c\\", "\\\""]);
+}
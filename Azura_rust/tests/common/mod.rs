@@ -0,0 +1,25 @@
+//! Helpers shared by the integration tests, each test only uses some of them
+#![allow(dead_code)]
+
+use azura::scanner::{Scanner, ScannerError, Token, TokenKind};
+
+/// All tokens in `source`, which has to scan without errors
+pub fn tokens(source: &str) -> Vec<Token<'_>> {
+    Scanner::new(source).map(Result::unwrap).collect()
+}
+
+/// The kinds of all tokens in `source`, without the end of file
+pub fn kinds(source: &str) -> Vec<TokenKind<'_>> {
+    kinds_of(Scanner::new(source))
+}
+
+/// The kinds of all `tokens` of a scanner, without the end of file
+pub fn kinds_of<'a>(
+    tokens: impl IntoIterator<Item = Result<Token<'a>, ScannerError<'a>>>,
+) -> Vec<TokenKind<'a>> {
+    tokens
+        .into_iter()
+        .map(|token| token.unwrap().kind)
+        .filter(|kind| *kind != TokenKind::Eof)
+        .collect()
+}
@@ -0,0 +1,341 @@
+use azura::vm::{InterpretError, Vm};
+
+/// What running a program has to result in
+#[derive(Clone, Copy)]
+enum Expect {
+    /// Everything printed by `info`, with a successful exit
+    Output(&'static str),
+    /// The message of the first compile error
+    CompileError(&'static str),
+    /// The message of the runtime error, after the given output
+    RuntimeError(&'static str, &'static str),
+}
+
+use Expect::*;
+
+fn check(name: &str, source: &str, expect: Expect) {
+    let mut vm = Vm::new(Vec::new());
+    let result = vm.interpret(source);
+    let output = String::from_utf8(vm.into_output()).unwrap();
+    match (expect, result) {
+        (Output(expected), Ok(())) => assert_eq!(output, expected, "{name}: output"),
+        (CompileError(expected), Err(InterpretError::Compile(errors))) => {
+            assert_eq!(errors[0].message, expected, "{name}: compile error");
+            assert_eq!(output, "", "{name}: output");
+        }
+        (RuntimeError(expected_output, expected), Err(InterpretError::Runtime(error))) => {
+            assert_eq!(error.message, expected, "{name}: runtime error");
+            assert_eq!(output, expected_output, "{name}: output");
+        }
+        (_, result) => panic!("{name}: unexpected result {result:?} with output {output:?}"),
+    }
+}
+
+const CASES: &[(&str, &str, Expect)] = &[
+    // Literals keep their type
+    ("int literal", "info 42;", Output("42\n")),
+    ("float literal", "info 42.0;", Output("42.0\n")),
+    ("radix literals", "info 0x1F + 0o17 + 0b11;", Output("49\n")),
+    ("exponent is float", "info 1e3;", Output("1000.0\n")),
+    // Promotion
+    ("int plus int", "info 1 + 2;", Output("3\n")),
+    ("int plus float", "info 1 + 2.5;", Output("3.5\n")),
+    ("float plus int", "info 2.5 + 1;", Output("3.5\n")),
+    ("float result stays float", "info 0.5 + 0.5;", Output("1.0\n")),
+    ("int times float", "info 2 * 1.5;", Output("3.0\n")),
+    ("negated int", "info -5;", Output("-5\n")),
+    ("negated float", "info -0.5;", Output("-0.5\n")),
+    // Division
+    ("int division truncates", "info 7 / 2;", Output("3\n")),
+    ("negative int division truncates", "info -7 / 2;", Output("-3\n")),
+    ("mixed division", "info 7 / 2.0;", Output("3.5\n")),
+    ("int division by zero", "info 1 / 0;", RuntimeError("", "Division by zero")),
+    ("float division by zero", "info 1.0 / 0;", Output("inf\n")),
+    // Remainder
+    ("remainder", "info 7 % 3;", Output("1\n")),
+    ("remainder takes sign of dividend", "info -7 % 3;", Output("-1\n")),
+    ("remainder of negative divisor", "info 7 % -3;", Output("1\n")),
+    ("float remainder", "info 7.5 % 2;", Output("1.5\n")),
+    ("remainder by zero", "info 1 % 0;", RuntimeError("", "Division by zero")),
+    (
+        "remainder of min by minus one",
+        "info (-9223372036854775807 - 1) % -1;",
+        Output("0\n"),
+    ),
+    // Bitwise operators
+    ("and", "info 12 & 10;", Output("8\n")),
+    ("or", "info 12 | 10;", Output("14\n")),
+    ("xor", "info 12 ^ 10;", Output("6\n")),
+    ("and of negative", "info -1 & 255;", Output("255\n")),
+    ("left shift", "info 1 << 10;", Output("1024\n")),
+    ("right shift", "info 1024 >> 3;", Output("128\n")),
+    ("right shift keeps sign", "info -16 >> 2;", Output("-4\n")),
+    ("right shift past width", "info -5 >> 200;", Output("-1\n")),
+    ("shift of zero", "info 0 << 1000;", Output("0\n")),
+    (
+        "negative shift",
+        "info 1 << -1;",
+        RuntimeError("", "Shift amount must not be negative"),
+    ),
+    (
+        "bitwise float",
+        "info 1.0 & 1;",
+        RuntimeError("", "Operands of `&` must be integers, got float and int"),
+    ),
+    (
+        "shift float",
+        "info 1 >> 1.0;",
+        RuntimeError("", "Operands of `>>` must be integers, got int and float"),
+    ),
+    // Precedence
+    ("factor before term", "info 1 + 2 * 3 % 4;", Output("3\n")),
+    ("term before shift", "info 1 << 2 + 1;", Output("8\n")),
+    ("shift before and", "info 3 & 1 << 1;", Output("2\n")),
+    ("and before xor before or", "info 1 | 6 ^ 3 & 5;", Output("7\n")),
+    ("bitwise before comparison", "info 5 & 1 == 1;", Output("true\n")),
+    ("grouping", "info (1 + 2) * 3;", Output("9\n")),
+    // Comparisons across types
+    ("int equals float", "info 1 == 1.0;", Output("true\n")),
+    ("int not equal to string", "info 1 != \"1\";", Output("true\n")),
+    ("int less than float", "info 1 < 1.5;", Output("true\n")),
+    ("float greater or equal int", "info 2.0 >= 2;", Output("true\n")),
+    ("nan is not ordered", "info 0.0 / 0 >= 0;", Output("false\n")),
+    (
+        "compare with string",
+        "info 1 < \"2\";",
+        RuntimeError("", "Operands of `<` must be numbers, got int and string"),
+    ),
+    // Other values
+    ("strings", "info \"a\" + \"b\";", Output("ab\n")),
+    ("escapes", "info \"a\\tb\\u{21}\";", Output("a\tb!\n")),
+    ("raw string", "info r\"a\\tb\";", Output("a\\tb\n")),
+    (
+        "multi-line string",
+        "info \"\"\"\n    one\n      two\n    \"\"\";\ninfo 1;",
+        Output("one\n  two\n1\n"),
+    ),
+    ("char", "info 'a';", Output("a\n")),
+    ("string plus char", "info \"ab\" + 'c';", Output("abc\n")),
+    ("chars compare", "info 'a' < 'b';", Output("true\n")),
+    ("char is not a string", "info 'a' == \"a\";", Output("false\n")),
+    ("byte is a number", "info b'a' + 1;", Output("98\n")),
+    ("byte equals int", "info b'\\n' == 10;", Output("true\n")),
+    ("byte shift", "info b'\\x01' << 8 | b'\\xff';", Output("511\n")),
+    ("bytes", "info b\"a\\x00\" + br\"\\n\";", Output("b\"a\\x00\\\\n\"\n")),
+    (
+        "add chars",
+        "info 'a' + 'b';",
+        RuntimeError(
+            "",
+            "Operands of `+` must be two numbers or two strings, got char and char",
+        ),
+    ),
+    (
+        "multi-codepoint char",
+        "info 'ab';",
+        CompileError(
+            "Failed to parse character literal: character literals must contain exactly one codepoint, found 2",
+        ),
+    ),
+    ("not", "info !nil == !false;", Output("true\n")),
+    (
+        "add int to string",
+        "info 1 + \"a\";",
+        RuntimeError(
+            "",
+            "Operands of `+` must be two numbers or two strings, got int and string",
+        ),
+    ),
+    (
+        "negate bool",
+        "info -true;",
+        RuntimeError("", "Operand of `-` must be a number, got bool"),
+    ),
+    // Variables
+    (
+        "globals",
+        "have a := 7\nhave b := 2\na = a * b\ninfo a;",
+        Output("14\n"),
+    ),
+    (
+        "locals shadow globals",
+        "have x := 1\n{ have x := 2 info x; }\ninfo x;",
+        Output("2\n1\n"),
+    ),
+    (
+        "compound assignment",
+        "have x := 5\nx += 1 x *= 4 x %= 7 x <<= 2 x |= 1 x ^= 3\ninfo x;",
+        Output("14\n"),
+    ),
+    (
+        "compound assignment of local",
+        "{ have x := 10 x /= 4 info x; }",
+        Output("2\n"),
+    ),
+    (
+        "undefined global",
+        "info 1;\ninfo y;",
+        RuntimeError("1\n", "Undefined variable 'y'."),
+    ),
+    (
+        "assign undefined global",
+        "y = 1",
+        RuntimeError(
+            "",
+            "Variable 'y' is undefined! \nTry doing something like 'have [your variable name] := 0'. Happy coding!",
+        ),
+    ),
+    // Compile errors
+    ("missing semicolon", "info 1", CompileError("Expected ';' after value! \nTry something like this 'info 1 + 1;' happy coding!")),
+    ("missing expression", "info ;", CompileError("Expect expression.")),
+    ("invalid target", "1 + 2 = 3", CompileError("Invalid assignment target!")),
+    (
+        "redeclared local",
+        "{ have a := 1 have a := 2 }",
+        CompileError("Already a variable with this name declared in the scope!"),
+    ),
+    (
+        "own initializer",
+        "{ have a := a }",
+        CompileError("Can't read local variable in its own initializer!"),
+    ),
+];
+
+#[cfg(not(feature = "bigint"))]
+const OVERFLOW_CASES: &[(&str, &str, Expect)] = &[
+    (
+        "addition overflow",
+        "info 9223372036854775807 + 1;",
+        RuntimeError("", "Integer overflow"),
+    ),
+    (
+        "multiplication overflow",
+        "info 4611686018427387904 * 2;",
+        RuntimeError("", "Integer overflow"),
+    ),
+    (
+        "division overflow",
+        "info (-9223372036854775807 - 1) / -1;",
+        RuntimeError("", "Integer overflow"),
+    ),
+    (
+        "negation overflow",
+        "info -(-9223372036854775807 - 1);",
+        RuntimeError("", "Integer overflow"),
+    ),
+    (
+        "shift overflow",
+        "info 3 << 62;",
+        RuntimeError("", "Integer overflow"),
+    ),
+    (
+        "large literal",
+        "info 9223372036854775808;",
+        CompileError("Integer literal is too large to be represented."),
+    ),
+];
+
+#[cfg(feature = "bigint")]
+const OVERFLOW_CASES: &[(&str, &str, Expect)] = &[
+    (
+        "addition promotes",
+        "info 9223372036854775807 + 1;",
+        Output("9223372036854775808\n"),
+    ),
+    (
+        "multiplication promotes",
+        "info 4611686018427387904 * 4;",
+        Output("18446744073709551616\n"),
+    ),
+    (
+        "division promotes",
+        "info (-9223372036854775807 - 1) / -1;",
+        Output("9223372036854775808\n"),
+    ),
+    (
+        "negation promotes",
+        "info -(-9223372036854775807 - 1);",
+        Output("9223372036854775808\n"),
+    ),
+    (
+        "shift promotes",
+        "info 1 << 64;",
+        Output("18446744073709551616\n"),
+    ),
+    (
+        "result is demoted",
+        "info 9223372036854775808 - 1 == 9223372036854775807;",
+        Output("true\n"),
+    ),
+    (
+        "big division truncates",
+        "info -18446744073709551617 / 2;",
+        Output("-9223372036854775808\n"),
+    ),
+    (
+        "big remainder",
+        "info 18446744073709551617 % 10;",
+        Output("7\n"),
+    ),
+    (
+        "big bitwise",
+        "info (1 << 70 | 1 << 3) & 1 << 3;",
+        Output("8\n"),
+    ),
+    ("big right shift", "info (1 << 100) >> 98;", Output("4\n")),
+    (
+        "big plus float",
+        "info 18446744073709551616 + 0.5;",
+        Output("1.8446744073709552e19\n"),
+    ),
+    (
+        "big division by zero",
+        "info 18446744073709551616 / 0;",
+        RuntimeError("", "Division by zero"),
+    ),
+];
+
+#[test]
+fn conformance() {
+    for (name, source, expect) in CASES {
+        check(name, source, *expect);
+    }
+}
+
+#[test]
+fn integer_overflow() {
+    for (name, source, expect) in OVERFLOW_CASES {
+        check(name, source, *expect);
+    }
+}
+
+#[test]
+fn globals_persist_between_runs() {
+    let mut vm = Vm::new(Vec::new());
+    vm.interpret("have x := 40").unwrap();
+    vm.interpret("x += 2").unwrap();
+    vm.interpret("info x;").unwrap();
+    assert_eq!(String::from_utf8(vm.into_output()).unwrap(), "42\n");
+}
+
+#[test]
+fn errors_match_the_c_implementation() {
+    let mut vm = Vm::new(Vec::new());
+    let error = vm.interpret("info 1\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "[line 2] Error at end: Expected ';' after value! \nTry something like this 'info 1 + 1;' happy coding!"
+    );
+    let error = vm.interpret("have x := 1\ninfo x + nil;").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Operands of `+` must be two numbers or two strings, got int and nil\n[line 2] in script"
+    );
+    let error = vm
+        .interpret("have s := \"\"\"\n  a\n  \"\"\"\ninfo -s;")
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Operand of `-` must be a number, got string\n[line 4] in script"
+    );
+}
@@ -0,0 +1,237 @@
+//! Runs the programs in `tests/differential` through both the C implementation and this one,
+//! comparing what is printed, the exit code and the lines errors are reported on. The programs are
+//! golden tests of this implementation as well, see `azura::golden`.
+//!
+//! Intentional differences are listed at the top of a program, naming what differs and why:
+//!
+//! ```text
+//! // divergence(stdout, exit): the C implementation crashes on comparisons
+//! ```
+//!
+//! Every difference is reported, and the test fails if one is not listed or if a listed one does
+//! not happen anymore. Run with `--nocapture` to see the report when everything passes.
+#![cfg(unix)]
+
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display},
+    fs,
+    io::ErrorKind,
+    os::unix::process::ExitStatusExt,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// What is compared between the two implementations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Aspect {
+    Stdout,
+    /// The exit code, or the signal that ended the process
+    Exit,
+    /// The lines given by `[line N]` on stderr. The messages themselves are worded differently.
+    Errors,
+}
+
+/// How one of the implementations ran a program
+#[derive(Debug, PartialEq, Eq)]
+struct Run {
+    stdout: String,
+    exit: Exit,
+    errors: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Exit {
+    Code(i32),
+    Signal(i32),
+}
+
+struct Case {
+    name: String,
+    args: Vec<String>,
+    /// The intentional divergences with the reason for them
+    expected: Vec<(Aspect, String)>,
+}
+
+const ASPECTS: [Aspect; 3] = [Aspect::Stdout, Aspect::Exit, Aspect::Errors];
+
+impl Aspect {
+    fn parse(name: &str) -> Option<Aspect> {
+        ASPECTS
+            .into_iter()
+            .find(|aspect| aspect.to_string() == name)
+    }
+    fn of(self, run: &Run) -> String {
+        match self {
+            Aspect::Stdout => format!("{:?}", run.stdout),
+            Aspect::Exit => run.exit.to_string(),
+            Aspect::Errors => format!("{:?}", run.errors),
+        }
+    }
+}
+
+impl Display for Aspect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Aspect::Stdout => "stdout",
+            Aspect::Exit => "exit",
+            Aspect::Errors => "errors",
+        })
+    }
+}
+
+impl Display for Exit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exit::Code(code) => write!(f, "exit code {code}"),
+            Exit::Signal(signal) => write!(f, "killed by signal {signal}"),
+        }
+    }
+}
+
+impl From<Output> for Run {
+    fn from(output: Output) -> Self {
+        let exit = match (output.status.code(), output.status.signal()) {
+            (Some(code), _) => Exit::Code(code),
+            (None, Some(signal)) => Exit::Signal(signal),
+            (None, None) => unreachable!("a process exits with a code or a signal"),
+        };
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let errors = stderr
+            .lines()
+            .filter_map(|line| line.strip_prefix("[line ")?.split_once(']'))
+            .filter_map(|(line, _)| line.parse().ok())
+            .collect();
+        Run {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            exit,
+            errors,
+        }
+    }
+}
+
+/// Reads the `// divergence(...): reason` lines at the top of a program
+fn expected_divergences(path: &Path, source: &str) -> Vec<(Aspect, String)> {
+    let mut expected = Vec::new();
+    let header = source.lines().map_while(|line| line.strip_prefix("//"));
+    for line in header {
+        let Some(rest) = line.trim().strip_prefix("divergence(") else {
+            continue;
+        };
+        let (aspects, reason) = rest
+            .split_once("):")
+            .unwrap_or_else(|| panic!("{}: expected `divergence(...): reason`", path.display()));
+        for name in aspects.split(',') {
+            let aspect = Aspect::parse(name.trim())
+                .unwrap_or_else(|| panic!("{}: unknown aspect `{}`", path.display(), name.trim()));
+            expected.push((aspect, reason.trim().to_owned()));
+        }
+    }
+    expected
+}
+
+fn corpus() -> Vec<Case> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/differential");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "az"))
+        .collect();
+    paths.sort();
+    let mut cases: Vec<Case> = paths
+        .into_iter()
+        .map(|path| {
+            let source = fs::read_to_string(&path).unwrap();
+            Case {
+                name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+                expected: expected_divergences(&path, &source),
+                args: vec![path.to_string_lossy().into_owned()],
+            }
+        })
+        .collect();
+    // The exit codes that do not come from running a program
+    cases.push(Case {
+        name: "usage".to_owned(),
+        args: vec!["one".to_owned(), "two".to_owned(), "three".to_owned()],
+        expected: Vec::new(),
+    });
+    cases.push(Case {
+        name: "missing file".to_owned(),
+        args: vec![dir.join("missing.az").to_string_lossy().into_owned()],
+        expected: Vec::new(),
+    });
+    cases
+}
+
+/// Builds the C implementation with its Makefile, without the debug output it prints by default.
+/// Gives back `None` if there is no `make` to build it with.
+fn build_c() -> Option<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let target = Path::new(env!("CARGO_TARGET_TMPDIR")).join("azura-c");
+    let output = Command::new("make")
+        .arg("-C")
+        .arg(root)
+        .arg(format!("TARGET={}", target.display()))
+        .arg("CFLAGS=-O2 -std=c17 -DNDEBUG")
+        .output();
+    match output {
+        Err(error) if error.kind() == ErrorKind::NotFound => None,
+        Err(error) => panic!("could not run make: {error}"),
+        Ok(output) => {
+            assert!(
+                output.status.success(),
+                "building the C implementation failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Some(target)
+        }
+    }
+}
+
+fn run(program: &Path, args: &[String]) -> Run {
+    Command::new(program)
+        .args(args)
+        .output()
+        .unwrap_or_else(|error| panic!("could not run {}: {error}", program.display()))
+        .into()
+}
+
+#[test]
+fn behaves_like_the_c_implementation() {
+    let Some(c) = build_c() else {
+        eprintln!("skipped, `make` is needed to build the C implementation");
+        return;
+    };
+    let rust = Path::new(env!("CARGO_BIN_EXE_azura"));
+
+    let mut report = String::new();
+    let mut failed = false;
+    for case in corpus() {
+        let (c_run, rust_run) = (run(&c, &case.args), run(rust, &case.args));
+        let diverged: BTreeSet<Aspect> = ASPECTS
+            .into_iter()
+            .filter(|aspect| aspect.of(&c_run) != aspect.of(&rust_run))
+            .collect();
+        let expected: BTreeSet<Aspect> = case.expected.iter().map(|(aspect, _)| *aspect).collect();
+        for aspect in diverged.union(&expected) {
+            let reason = case
+                .expected
+                .iter()
+                .find(|(expected, _)| expected == aspect);
+            let (status, allowed) = match (diverged.contains(aspect), reason) {
+                (true, Some((_, reason))) => (format!("intentional, {reason}"), true),
+                (true, None) => ("UNEXPECTED".to_owned(), false),
+                (false, _) => ("STALE, listed but did not happen".to_owned(), false),
+            };
+            failed |= !allowed;
+            report += &format!(
+                "{}: {aspect} ({status})\n    c:    {}\n    rust: {}\n",
+                case.name,
+                aspect.of(&c_run),
+                aspect.of(&rust_run),
+            );
+        }
+    }
+    eprint!("{report}");
+    assert!(!failed, "the implementations diverge:\n{report}");
+}
@@ -0,0 +1,42 @@
+use azura::{
+    compiler::{compile, ErrorLocation},
+    scanner::{Scanner, Span, TokenKind},
+};
+
+#[test]
+fn eof_token_ends_every_scan() {
+    for (source, line, pos) in [("", 1, 1), ("info 1;", 1, 8), ("x\n// done\n", 3, 1)] {
+        let eof = Scanner::new(source).map(Result::unwrap).last().unwrap();
+        assert_eq!(eof.kind, TokenKind::Eof, "{source:?}");
+        assert_eq!((eof.line, eof.pos), (line, pos), "{source:?}");
+        assert_eq!(eof.lexeme, "");
+        assert_eq!(
+            eof.span,
+            Span {
+                start: source.len(),
+                end: source.len()
+            }
+        );
+    }
+    assert_eq!(TokenKind::Eof.to_string(), "end of file");
+}
+
+#[test]
+fn errors_at_the_end_point_at_the_eof_token() {
+    let source = "{\n  info 1;\n";
+    let errors = compile(source).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    let error = &errors[0];
+    assert_eq!(error.location, ErrorLocation::End);
+    assert_eq!(
+        error.span,
+        Span {
+            start: source.len(),
+            end: source.len()
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "[line 3] Error at end: Expect '}' after block."
+    );
+}
@@ -0,0 +1,223 @@
+use std::{fs, path::PathBuf, process::Command};
+
+use azura::{
+    compiler::compile,
+    formatter::{format, FormatConfig},
+};
+use proptest::prelude::*;
+
+fn fmt(source: &str) -> String {
+    format(source, &FormatConfig::default()).unwrap()
+}
+
+fn fmt_width(source: &str, max_width: usize) -> String {
+    let config = FormatConfig {
+        max_width,
+        ..FormatConfig::default()
+    };
+    format(source, &config).unwrap()
+}
+
+#[test]
+fn spacing_and_blocks() {
+    assert_eq!(
+        fmt("have   x:=1+2*-3 {have y:=x y*=2 info(y)  ;{}}x=!true"),
+        "\
+have x := 1 + 2 * -3
+{
+    have y := x
+    y *= 2
+    info (y);
+    {}
+}
+x = !true
+"
+    );
+    assert_eq!(fmt("info - -1;"), "info - -1;\n");
+    assert_eq!(fmt(""), "");
+    assert_eq!(fmt("\n\n"), "");
+}
+
+#[test]
+fn test_az_is_formatted() {
+    let source = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../test.az")).unwrap();
+    assert_eq!(fmt(&source), source);
+}
+
+#[test]
+fn comments_are_kept() {
+    assert_eq!(
+        fmt("#!/usr/bin/env azura\n/// docs\nhave x:=1 // one\n\n\n\n/* two */ info x; {\n\n  // inner\n\n info x;\n  // last\n\n}\n// end"),
+        "\
+#!/usr/bin/env azura
+/// docs
+have x := 1 // one
+
+/* two */
+info x;
+{
+    // inner
+
+    info x;
+    // last
+}
+// end
+"
+    );
+    assert_eq!(fmt("{ // open\n}"), "{ // open\n}\n");
+}
+
+#[test]
+fn statements_with_comments_inside_are_kept_as_written() {
+    assert_eq!(
+        fmt("{\n        info 1 +   // one\n  2;\n}"),
+        "{\n    info 1 +   // one\n  2;\n}\n"
+    );
+}
+
+#[test]
+fn pragmas_stay_first() {
+    assert_eq!(
+        fmt("#edition 2   \n#allow(unused)\n\ninfo 1;"),
+        "#edition 2\n#allow(unused)\n\ninfo 1;\n"
+    );
+}
+
+#[test]
+fn long_lines_are_broken() {
+    assert_eq!(
+        fmt_width("info 1000 + 2000 * 3000 - (4000 + 5000);", 24),
+        "\
+info 1000
+    + 2000 * 3000
+    - (4000 + 5000);
+"
+    );
+    assert_eq!(
+        fmt_width("{have total := (100000 + 200000 + 300000) * 2}", 30),
+        "\
+{
+    have total := (100000
+            + 200000
+            + 300000)
+        * 2
+}
+"
+    );
+    // Lines that fit are left alone
+    assert_eq!(fmt_width("info 1 + 2;", 11), "info 1 + 2;\n");
+}
+
+#[test]
+fn compile_errors_are_returned() {
+    let errors = format("have x := ;", &FormatConfig::default()).unwrap_err();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn command() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let messy = dir.join("fmt_messy.az");
+    let tidy = dir.join("fmt_tidy.az");
+    let broken = dir.join("fmt_broken.az");
+    fs::write(&messy, "have x:=1\ninfo x ;").unwrap();
+    fs::write(&tidy, "info 1;\n").unwrap();
+    fs::write(&broken, "info").unwrap();
+    let run = |args: &[&str], paths: &[&PathBuf]| {
+        Command::new(env!("CARGO_BIN_EXE_azura"))
+            .arg("fmt")
+            .args(args)
+            .args(paths)
+            .output()
+            .unwrap()
+    };
+
+    let check = run(&["--check"], &[&messy, &tidy]);
+    assert_eq!(check.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(check.stdout).unwrap(),
+        format!("{}\n", messy.display())
+    );
+    assert_eq!(fs::read_to_string(&messy).unwrap(), "have x:=1\ninfo x ;");
+
+    assert!(run(&[], &[&messy, &tidy]).status.success());
+    assert_eq!(
+        fs::read_to_string(&messy).unwrap(),
+        "have x := 1\ninfo x;\n"
+    );
+    assert!(run(&["--check"], &[&messy, &tidy]).status.success());
+
+    assert_eq!(run(&[], &[&broken]).status.code(), Some(65));
+    assert_eq!(run(&["--max-width=wide"], &[&tidy]).status.code(), Some(64));
+}
+
+/// Expressions built from the whole grammar, with random spacing
+fn expression() -> impl Strategy<Value = String> {
+    let leaf = prop_oneof![
+        "[a-c]",
+        "[0-9]{1,3}",
+        Just("1.5".to_owned()),
+        Just("\"s\"".to_owned()),
+        Just("true".to_owned()),
+        Just("nil".to_owned()),
+    ];
+    leaf.prop_recursive(4, 24, 2, |inner| {
+        let operator = prop_oneof![
+            "[-+*/%<>&|^]",
+            Just("==".to_owned()),
+            Just("!=".to_owned()),
+            Just("<=".to_owned()),
+            Just("<<".to_owned()),
+        ];
+        prop_oneof![
+            (inner.clone(), operator, inner.clone(), " {0,2}")
+                .prop_map(|(left, op, right, space)| format!("{left}{space}{op}{space}{right}")),
+            inner.clone().prop_map(|expr| format!("( {expr})")),
+            ("[-!]", inner.clone()).prop_map(|(op, expr)| format!("{op} {expr}")),
+            ("[a-c]", "(=|\\+=|<<=)", inner)
+                .prop_map(|(name, op, expr)| format!("{name}{op}{expr}")),
+        ]
+    })
+}
+
+fn statement() -> impl Strategy<Value = String> {
+    let simple = prop_oneof![
+        ("[a-c]", expression()).prop_map(|(name, expr)| format!("have {name}:={expr}")),
+        "have [a-c]",
+        expression().prop_map(|expr| format!("info {expr};")),
+        expression(),
+    ];
+    simple.prop_recursive(2, 12, 4, |inner| {
+        prop::collection::vec(inner, 0..4).prop_map(|items| format!("{{{}}}", items.join(" ")))
+    })
+}
+
+fn program() -> impl Strategy<Value = String> {
+    let separator = prop_oneof![
+        " {1,2}",
+        "\n{1,3}",
+        Just(" // note\n".to_owned()),
+        Just("\n/* note */ ".to_owned()),
+    ];
+    prop::collection::vec((statement(), separator), 0..6).prop_map(|items| {
+        items
+            .into_iter()
+            .map(|(statement, separator)| statement + &separator)
+            .collect()
+    })
+}
+
+proptest! {
+    #[test]
+    fn formatting_keeps_the_meaning_and_is_stable(source in program(), max_width in 10usize..60) {
+        let Ok(original) = compile(&source) else {
+            return Ok(());
+        };
+        let config = FormatConfig { max_width, ..FormatConfig::default() };
+        let formatted = format(&source, &config).unwrap();
+        let chunk = compile(&formatted).unwrap();
+        prop_assert_eq!(&chunk.code, &original.code, "{}", formatted);
+        prop_assert_eq!(&chunk.constants, &original.constants, "{}", formatted);
+        prop_assert_eq!(format(&formatted, &config).unwrap(), formatted);
+    }
+}
@@ -0,0 +1,109 @@
+//! Runs the golden tests in `tests/**/*.az`, the same as `azura test tests`. Set `AZURA_BLESS=1`
+//! to write what the scripts result in back into them.
+use std::{env, path::Path};
+
+use azura::golden::{self, bless, diff, expectations, DiffLine, Expectation};
+
+#[test]
+fn scripts() {
+    let bless = env::var_os("AZURA_BLESS").is_some();
+    let scripts = golden::discover(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")).unwrap();
+    assert!(!scripts.is_empty());
+    let mut report = String::new();
+    for script in scripts {
+        let outcome = if bless {
+            golden::bless_file(&script)
+        } else {
+            golden::check(&script)
+        }
+        .unwrap();
+        if outcome.passed() || bless {
+            continue;
+        }
+        report += &format!("{}\n", script.display());
+        for line in diff(&outcome.expected, &outcome.actual) {
+            report += &format!("{line}\n");
+        }
+    }
+    assert!(report.is_empty(), "scripts failed:\n{report}");
+}
+
+#[test]
+fn expectations_are_read_from_line_comments() {
+    let source = "info 1; // expect: 1\n/* expect: 2 */\n// expect:  padded \ninfo \"// expect: 3\";\n// expect error: [line 1] Error: x\n// expect runtime error: [line 2] y\n// expected: 4";
+    let found: Vec<Expectation> = expectations(source)
+        .into_iter()
+        .map(|expected| expected.expectation)
+        .collect();
+    assert_eq!(
+        found,
+        [
+            Expectation::Output("1".to_owned()),
+            Expectation::Output(" padded ".to_owned()),
+            Expectation::CompileError("[line 1] Error: x".to_owned()),
+            Expectation::RuntimeError("[line 2] y".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn errors_are_written_on_one_line() {
+    assert_eq!(
+        golden::run("info 1;\ninfo 1 + nil;"),
+        [
+            Expectation::Output("1".to_owned()),
+            Expectation::RuntimeError(
+                "[line 2] Operands of `+` must be two numbers or two strings, got int and nil"
+                    .to_owned()
+            ),
+        ]
+    );
+    assert_eq!(
+        golden::run("info 1"),
+        [Expectation::CompileError(
+            "[line 1] Error at end: Expected ';' after value! \\nTry something like this 'info 1 + 1;' happy coding!"
+                .to_owned()
+        )]
+    );
+}
+
+#[test]
+fn blessing_changes_comments_in_place() {
+    let source = "info 1; // expect: 2\ninfo 3;   // expect: 4\n";
+    let blessed = bless(source, &golden::run(source));
+    assert_eq!(blessed, "info 1; // expect: 1\ninfo 3;   // expect: 3\n");
+}
+
+#[test]
+fn blessing_removes_comments_that_are_left_over() {
+    let source = "info 1; // expect: 1\n// expect: 2\ninfo 3;\n// expect: 3\n// expect: 4";
+    let blessed = bless(source, &golden::run("info 1;"));
+    assert_eq!(blessed, "info 1; // expect: 1\ninfo 3;\n");
+}
+
+#[test]
+fn blessing_adds_missing_comments_to_the_end() {
+    let source = "info 1; // expect: 1\ninfo 2;\ninfo -nil;";
+    let blessed = bless(source, &golden::run(source));
+    assert_eq!(
+        blessed,
+        "info 1; // expect: 1\ninfo 2;\ninfo -nil;\n// expect: 2\n// expect runtime error: [line 3] Operand of `-` must be a number, got nil\n"
+    );
+    assert_eq!(bless(&blessed, &golden::run(&blessed)), blessed);
+}
+
+#[test]
+fn diffs_keep_what_is_the_same() {
+    let output = |text: &str| Expectation::Output(text.to_owned());
+    let expected = [output("1"), output("2"), output("3")];
+    let actual = [output("1"), output("3"), output("4")];
+    assert_eq!(
+        diff(&expected, &actual),
+        [
+            DiffLine::Same(&output("1")),
+            DiffLine::Missing(&output("2")),
+            DiffLine::Same(&output("3")),
+            DiffLine::Unexpected(&output("4")),
+        ]
+    );
+}
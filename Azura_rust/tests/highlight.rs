@@ -0,0 +1,138 @@
+use std::{fs, path::PathBuf, process::Command};
+
+use azura::highlight::{self, Highlight};
+use proptest::prelude::*;
+
+/// Removes the ANSI escape codes from highlighted text
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        stripped += &rest[..start];
+        let end = rest[start..].find('m').unwrap();
+        rest = &rest[start + end + 1..];
+    }
+    stripped + rest
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[test]
+fn tokens_are_classified() {
+    let pieces: Vec<_> = highlight::highlights(
+        "#edition 2\ninfo x + 1.5; // done\nhave s := nil\n@ 'c' \"s\" /// docs\n",
+    )
+    .filter_map(|(highlight, text)| Some((highlight?, text)))
+    .collect();
+    assert_eq!(
+        pieces,
+        [
+            (Highlight::Pragma, "#edition 2"),
+            (Highlight::Keyword, "info"),
+            (Highlight::Ident, "x"),
+            (Highlight::Operator, "+"),
+            (Highlight::Number, "1.5"),
+            (Highlight::Punctuation, ";"),
+            (Highlight::Comment, "// done"),
+            (Highlight::Keyword, "have"),
+            (Highlight::Ident, "s"),
+            (Highlight::Operator, ":="),
+            (Highlight::Constant, "nil"),
+            (Highlight::Error, "@"),
+            (Highlight::Char, "'c'"),
+            (Highlight::String, "\"s\""),
+            (Highlight::DocComment, "/// docs"),
+        ]
+    );
+}
+
+#[test]
+fn keywords_follow_the_edition() {
+    let switch = |source| {
+        highlight::highlights(source)
+            .find(|(_, text)| *text == "switch")
+            .and_then(|(highlight, _)| highlight)
+    };
+    assert_eq!(switch("switch"), Some(Highlight::Keyword));
+    assert_eq!(switch("#edition 1\nswitch"), Some(Highlight::Ident));
+    // Like the compiler, pragmas after code are ignored
+    assert_eq!(switch("x\n#edition 1\nswitch"), Some(Highlight::Keyword));
+}
+
+#[test]
+fn ansi_styles_every_line_on_its_own() {
+    let highlighted = highlight::ansi("/* one\ntwo */");
+    assert_eq!(highlighted.lines().count(), 2);
+    for line in highlighted.lines() {
+        assert!(
+            line.starts_with("\x1b[") && line.ends_with("\x1b[0m"),
+            "{line:?}"
+        );
+    }
+}
+
+#[test]
+fn html_is_escaped() {
+    let html = highlight::html_fragment("info \"<b>&\" < 'x';");
+    assert_eq!(
+        html,
+        "<pre class=\"azura\"><code><span class=\"az-keyword\">info</span> \
+         <span class=\"az-string\">&quot;&lt;b&gt;&amp;&quot;</span> \
+         <span class=\"az-operator\">&lt;</span> \
+         <span class=\"az-char\">&#39;x&#39;</span>\
+         <span class=\"az-punctuation\">;</span></code></pre>"
+    );
+    let page = highlight::html("info 1;", "a <title>");
+    assert!(page.starts_with("<!DOCTYPE html>"));
+    assert!(page.contains("<title>a &lt;title&gt;</title>"));
+    assert!(page.contains(highlight::CSS));
+}
+
+#[test]
+fn command() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("highlight.az");
+    fs::write(&path, "info \"unterminated\n@").unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_azura"))
+            .arg("highlight")
+            .args(args)
+            .arg(&path)
+            .output()
+            .unwrap()
+    };
+    let ansi = run(&[]);
+    assert!(ansi.status.success());
+    assert_eq!(
+        strip_ansi(&String::from_utf8(ansi.stdout).unwrap()),
+        "info \"unterminated\n@"
+    );
+    let html = run(&["--format=html"]);
+    assert!(html.status.success());
+    assert!(String::from_utf8(html.stdout)
+        .unwrap()
+        .contains("<span class=\"az-error\">"));
+    assert_eq!(run(&["--format=svg"]).status.code(), Some(64));
+}
+
+proptest! {
+    #[test]
+    fn all_of_the_source_is_kept(source in "([ \t\n/*a-z0-9_.'\"#!<>&{};:=@-]|é|\u{301}){0,48}") {
+        let pieces: String = highlight::highlights(&source).map(|(_, text)| text).collect();
+        prop_assert_eq!(&pieces, &source);
+        prop_assert_eq!(strip_ansi(&highlight::ansi(&source)), source.clone());
+        let html = highlight::html_fragment(&source);
+        let mut text = String::new();
+        let mut rest = html.as_str();
+        while let Some(start) = rest.find('<') {
+            text += &rest[..start];
+            rest = &rest[start + rest[start..].find('>').unwrap() + 1..];
+        }
+        prop_assert_eq!(unescape_html(&(text + rest)), source);
+    }
+}
@@ -0,0 +1,154 @@
+use azura::scanner::{IncrementalScanner, Lexed, Scanner, TextEdit};
+use proptest::prelude::*;
+
+/// Source text made of pieces that open and close strings, comments and pragmas
+fn text(max: usize) -> impl Strategy<Value = String> {
+    let piece = prop_oneof![
+        "[ \n/*a-z0-9_.'\"#\\\\]{1,6}",
+        Just("\"".to_owned()),
+        Just("\"\"\"".to_owned()),
+        Just("r#\"".to_owned()),
+        Just("\"#".to_owned()),
+        Just("/*".to_owned()),
+        Just("*/".to_owned()),
+        Just("//".to_owned()),
+        Just("\n#edition 2\n".to_owned()),
+        Just("x.0.1".to_owned()),
+        Just("é".to_owned()),
+    ];
+    prop::collection::vec(piece, 0..max).prop_map(|pieces| pieces.concat())
+}
+
+/// Edits given as fractions of the source length, so that they fit any source
+fn edits() -> impl Strategy<Value = Vec<(f64, f64, String)>> {
+    prop::collection::vec((0.0..=1.0, 0.0..=0.2, text(3)), 1..6)
+}
+
+#[allow(clippy::result_large_err)]
+fn full_scan(source: &str, trivia: bool) -> Vec<Lexed> {
+    let scanner = if trivia {
+        Scanner::new(source).with_trivia()
+    } else {
+        Scanner::new(source)
+    };
+    scanner
+        .map(|result| {
+            result
+                .map(|token| token.into_owned())
+                .map_err(|error| error.into_owned())
+        })
+        .collect()
+}
+
+/// The char boundary closest to `fraction` of the way through `source`
+fn boundary(source: &str, fraction: f64) -> usize {
+    let mut idx = (source.len() as f64 * fraction) as usize;
+    while !source.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+fn check_edits(
+    source: String,
+    edits: Vec<(f64, f64, String)>,
+    trivia: bool,
+) -> Result<(), TestCaseError> {
+    let mut incremental = if trivia {
+        IncrementalScanner::with_trivia(source)
+    } else {
+        IncrementalScanner::new(source)
+    };
+    for (at, length, text) in edits {
+        let start = boundary(incremental.source(), at);
+        let end = boundary(incremental.source(), (at + length).min(1.0)).max(start);
+        let changed = incremental.edit(TextEdit {
+            range: start..end,
+            text: &text,
+        });
+        let expected = full_scan(incremental.source(), trivia);
+        prop_assert_eq!(
+            incremental.tokens(),
+            expected.as_slice(),
+            "{:?}",
+            incremental.source()
+        );
+        prop_assert!(changed.end <= expected.len());
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn edits_match_full_scans(source in text(40), edits in edits()) {
+        check_edits(source, edits, false)?;
+    }
+
+    #[test]
+    fn edits_match_full_scans_with_trivia(source in text(40), edits in edits()) {
+        check_edits(source, edits, true)?;
+    }
+}
+
+fn edit(scanner: &mut IncrementalScanner, find: &str, text: &str) -> std::ops::Range<usize> {
+    let start = scanner.source().find(find).unwrap();
+    let changed = scanner.edit(TextEdit {
+        range: start..start + find.len(),
+        text,
+    });
+    assert_eq!(
+        scanner.tokens(),
+        full_scan(scanner.source(), false).as_slice()
+    );
+    changed
+}
+
+#[test]
+fn only_the_edited_line_is_scanned_again() {
+    let source = "have a := 1;\n".repeat(100);
+    let mut scanner = IncrementalScanner::new(source);
+    let line = scanner.source().find("have").unwrap() + 13 * 50;
+    let changed = scanner.edit(TextEdit {
+        range: line + 5..line + 6,
+        text: "bcd",
+    });
+    // `have`, `bcd`, `:=`, `1` and `;`
+    assert_eq!(changed.len(), 5);
+    assert_eq!(changed.start, 5 * 50);
+}
+
+#[test]
+fn opening_and_closing_block_comments() {
+    let mut scanner = IncrementalScanner::new("a\nb\nc\nd\n");
+    // Everything after the opened comment becomes part of it, leaving an error and the end
+    let changed = edit(&mut scanner, "b", "/* b");
+    assert_eq!(changed.len(), 2);
+    assert_eq!(scanner.tokens().len(), 3);
+    let changed = edit(&mut scanner, "c", "*/ c");
+    assert_eq!(changed.len(), 3);
+    assert_eq!(scanner.tokens().len(), 4);
+}
+
+#[test]
+fn opening_and_closing_strings() {
+    let mut scanner = IncrementalScanner::new("x := 1\ny := \"a\"\nz := 2\n");
+    edit(&mut scanner, "1", "\"\"\" 1");
+    edit(&mut scanner, "2", "2 \"\"\"");
+    edit(&mut scanner, "\"\"\" 1", "1");
+    edit(&mut scanner, "a\"", "a");
+    edit(&mut scanner, "2", "\"2");
+}
+
+#[test]
+fn edits_at_the_ends() {
+    let mut scanner = IncrementalScanner::new("");
+    edit(&mut scanner, "", "a\n");
+    let len = scanner.source().len();
+    scanner.edit(TextEdit {
+        range: len..len,
+        text: "b",
+    });
+    assert_eq!(scanner.tokens(), full_scan("a\nb", false).as_slice());
+    edit(&mut scanner, "a\nb", "");
+    assert_eq!(scanner.tokens().len(), 1);
+}
@@ -0,0 +1,132 @@
+use std::collections::{BTreeMap, HashMap};
+
+use azura::{
+    keywords::{Edition, Keyword, KeywordTable, KEYWORDS},
+    scanner::{Scanner, TokenKind},
+};
+
+mod common;
+
+use common::kinds_of;
+
+/// Like [`common::kinds`], with the keywords of `keywords` reserved as of `edition`
+fn kinds<K: KeywordTable>(source: &str, keywords: K, edition: Edition) -> Vec<TokenKind<'_>> {
+    kinds_of(
+        Scanner::new(source)
+            .with_keywords(keywords)
+            .edition(edition),
+    )
+}
+
+use TokenKind::{CBrace, Ident, OBrace};
+
+const SWITCH: TokenKind = TokenKind::Keyword(Keyword::Switch);
+const CASE: TokenKind = TokenKind::Keyword(Keyword::Case);
+
+/// Only knows a single keyword, spelled differently
+struct Spanish;
+
+impl KeywordTable for Spanish {
+    fn keyword(&self, ident: &str) -> Option<Keyword> {
+        (ident == "si").then_some(Keyword::If)
+    }
+}
+
+#[test]
+fn any_table() {
+    let if_ = TokenKind::Keyword(Keyword::If);
+    let expected = [if_.clone(), Ident("x")];
+    let hash_map = HashMap::from([("if", Keyword::If)]);
+    let owned: HashMap<String, Keyword> = HashMap::from([("if".to_owned(), Keyword::If)]);
+    let btree_map = BTreeMap::from([("if", Keyword::If)]);
+    let list = [("if", Keyword::If)];
+    assert_eq!(kinds("if x", &KEYWORDS, Edition::V1), expected);
+    assert_eq!(kinds("if x", &hash_map, Edition::V1), expected);
+    assert_eq!(kinds("if x", &owned, Edition::V1), expected);
+    assert_eq!(kinds("if x", btree_map, Edition::V1), expected);
+    assert_eq!(kinds("if x", list.as_slice(), Edition::V1), expected);
+    assert_eq!(kinds("si if", Spanish, Edition::V1), [if_, Ident("if")]);
+}
+
+#[test]
+fn newer_keywords_are_identifiers_in_older_editions() {
+    assert_eq!(
+        kinds("switch := 1", &KEYWORDS, Edition::V1)[0],
+        Ident("switch")
+    );
+    assert_eq!(kinds("switch := 1", &KEYWORDS, Edition::V2)[0], SWITCH);
+    assert_eq!(
+        kinds("have", &KEYWORDS, Edition::V1),
+        [TokenKind::Keyword(Keyword::Have)]
+    );
+    // A newer edition never loses keywords
+    for keyword in KEYWORDS.values() {
+        assert!(keyword.edition() <= Edition::LATEST, "{keyword}");
+    }
+}
+
+#[test]
+fn case_is_only_reserved_inside_switch() {
+    assert_eq!(
+        kinds(
+            "case switch x { case { case } } case",
+            &KEYWORDS,
+            Edition::V2
+        ),
+        [
+            Ident("case"),
+            SWITCH,
+            Ident("x"),
+            OBrace,
+            CASE,
+            OBrace,
+            CASE,
+            CBrace,
+            CBrace,
+            Ident("case")
+        ]
+    );
+    // Blocks before the switch body do not count
+    assert_eq!(
+        kinds("{ case } switch", &KEYWORDS, Edition::V2),
+        [OBrace, Ident("case"), CBrace, SWITCH]
+    );
+    // Neither are `switch` or `case` reserved before edition 2
+    assert_eq!(
+        kinds("switch { case }", &KEYWORDS, Edition::V1),
+        [Ident("switch"), OBrace, Ident("case"), CBrace]
+    );
+}
+
+#[test]
+fn baseline_keywords_are_reserved_by_default() {
+    assert_eq!(
+        kinds_of(Scanner::new("switch x { case }").with_keywords(&KEYWORDS)),
+        [SWITCH, Ident("x"), OBrace, CASE, CBrace]
+    );
+}
+
+#[test]
+fn edition_pragma_only_counts_before_code() {
+    let switch = |source| {
+        kinds_of(Scanner::new(source).with_keywords(&KEYWORDS))
+            .into_iter()
+            .find(|kind| *kind == Ident("switch") || *kind == SWITCH)
+            .unwrap()
+    };
+    assert_eq!(switch("#edition 1\nswitch"), Ident("switch"));
+    assert_eq!(switch("/// docs\n#edition 1\nswitch"), Ident("switch"));
+    assert_eq!(switch("x\n#edition 1\nswitch"), SWITCH);
+}
+
+#[test]
+fn editions() {
+    assert_eq!(Edition::default(), Edition::V2);
+    for number in 1..=2 {
+        let edition = Edition::from_number(number).unwrap();
+        assert_eq!(edition.number(), number);
+        assert_eq!(edition.to_string(), number.to_string());
+    }
+    assert_eq!(Edition::from_number(0), None);
+    assert_eq!(Edition::from_number(3), None);
+}
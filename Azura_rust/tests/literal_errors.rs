@@ -0,0 +1,53 @@
+use std::error::Error;
+
+use azura::scanner::{LiteralError, Scanner, ScannerError, ScannerErrorKind};
+
+/// The first error found in `source`
+fn error(source: &str) -> ScannerError<'_> {
+    Scanner::new(source)
+        .find_map(Result::err)
+        .unwrap_or_else(|| panic!("{source:?} should not scan"))
+}
+
+fn literal_error(source: &str) -> LiteralError {
+    match error(source).kind {
+        ScannerErrorKind::IncorrectLiteral { error } => error,
+        other => panic!("expected an incorrect literal in {source:?}, got {other:?}"),
+    }
+}
+
+#[test]
+fn kinds() {
+    use LiteralError::*;
+    assert_eq!(literal_error("1e999"), Overflow);
+    assert_eq!(
+        literal_error("0b102"),
+        InvalidDigit {
+            digit: '2',
+            radix: 2
+        }
+    );
+    assert_eq!(literal_error("0x"), EmptyDigits);
+    assert_eq!(literal_error("0b1.1"), FloatWithRadix { radix: 2 });
+    assert_eq!(literal_error("1.0e"), MalformedExponent);
+}
+
+#[test]
+fn errors_are_comparable() {
+    let first = error("info 0o19;");
+    assert_eq!(first, error("info 0o19;"));
+    assert_eq!(first.clone(), first);
+    assert_ne!(first, error("info 0o18;"));
+    assert_ne!(literal_error("0b2"), literal_error("0o9"));
+}
+
+#[test]
+fn literal_error_is_the_source() {
+    let error = error("0o19");
+    let source = error.source().expect("the literal error is the source");
+    assert_eq!(
+        source.to_string(),
+        "digit `9` is not valid in an octal literal"
+    );
+    assert!(self::error("\"open").source().is_none());
+}
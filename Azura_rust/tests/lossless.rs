@@ -0,0 +1,54 @@
+use azura::scanner::{Scanner, ScannerError, Token, TokenKind};
+use proptest::prelude::*;
+
+/// Source text biased towards characters the scanner treats specially
+fn source() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[ \t\r\n/*a-z0-9_.'\"@#$(){}\\[\\];:,=<>!?~+%&|^éλ-]{0,64}",
+        any::<String>(),
+    ]
+}
+
+fn is_trivia(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Whitespace(_)
+            | TokenKind::Newline
+            | TokenKind::LineComment(_)
+            | TokenKind::BlockComment(_)
+            | TokenKind::Shebang(_)
+    )
+}
+
+/// The parts of a token or error that the trivia mode must leave alone
+fn describe<'a>(token: Result<Token<'a>, ScannerError<'a>>) -> String {
+    match token {
+        Ok(token) => format!("{:?} on line {}", token.kind, token.line),
+        Err(error) => format!("{:?} on line {}", error.kind, error.line),
+    }
+}
+
+proptest! {
+    #[test]
+    fn trivia_mode_round_trips(source in source()) {
+        let output: String = Scanner::new(&source)
+            .with_trivia()
+            .map(|token| match token {
+                Ok(token) => token.lexeme,
+                Err(error) => error.lexeme,
+            })
+            .collect();
+        prop_assert_eq!(output, source);
+    }
+
+    #[test]
+    fn trivia_mode_does_not_change_other_tokens(source in source()) {
+        let lossy: Vec<_> = Scanner::new(&source).map(describe).collect();
+        let lossless: Vec<_> = Scanner::new(&source)
+            .with_trivia()
+            .filter(|token| !matches!(token, Ok(token) if is_trivia(&token.kind)))
+            .map(describe)
+            .collect();
+        prop_assert_eq!(lossy, lossless);
+    }
+}
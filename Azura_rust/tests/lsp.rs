@@ -0,0 +1,324 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use serde_json::{json, Value};
+
+const URI: &str = "file:///test.az";
+
+/// Speaks JSON-RPC with `azura-lsp` like an editor would, one message at a time
+struct Client {
+    server: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+    /// Notifications received while waiting for a response
+    notifications: Vec<Value>,
+}
+
+impl Client {
+    /// Starts the server and goes through the initialization
+    fn start() -> (Client, Value) {
+        let mut server = Command::new(env!("CARGO_BIN_EXE_azura-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut client = Client {
+            stdin: server.stdin.take().unwrap(),
+            stdout: BufReader::new(server.stdout.take().unwrap()),
+            server,
+            next_id: 0,
+            notifications: Vec::new(),
+        };
+        let result = client.request(
+            "initialize",
+            json!({"processId": null, "rootUri": null, "capabilities": {}}),
+        );
+        client.notify("initialized", json!({}));
+        (client, result)
+    }
+
+    fn send(&mut self, message: Value) {
+        let body = message.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                length = Some(value.parse().unwrap());
+            }
+        }
+        let mut body = vec![0; length.expect("a Content-Length header")];
+        self.stdout.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}));
+    }
+
+    /// Sends a request and waits for its response, giving back the whole response
+    fn call(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}));
+        loop {
+            let message = self.receive();
+            if message["id"] == id {
+                return message;
+            }
+            self.notifications.push(message);
+        }
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let response = self.call(method, params);
+        assert_eq!(response["error"], Value::Null, "{response}");
+        response["result"].clone()
+    }
+
+    /// The next diagnostics published by the server
+    fn diagnostics(&mut self) -> Value {
+        let message = if self.notifications.is_empty() {
+            self.receive()
+        } else {
+            self.notifications.remove(0)
+        };
+        assert_eq!(message["method"], "textDocument/publishDiagnostics");
+        message["params"].clone()
+    }
+
+    fn at(&mut self, method: &str, line: u32, character: u32) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": {"uri": URI},
+                "position": {"line": line, "character": character},
+                "context": {"includeDeclaration": true},
+            }),
+        )
+    }
+
+    fn shutdown(mut self) {
+        assert_eq!(self.request("shutdown", Value::Null), Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.server.wait().unwrap().success());
+    }
+}
+
+fn range(line: u32, start: u32, end: u32) -> Value {
+    json!({
+        "start": {"line": line, "character": start},
+        "end": {"line": line, "character": end},
+    })
+}
+
+fn open(client: &mut Client, text: &str) {
+    client.notify(
+        "textDocument/didOpen",
+        json!({"textDocument": {"uri": URI, "languageId": "azura", "version": 1, "text": text}}),
+    );
+}
+
+const SOURCE: &str = "\
+have total := 1
+{
+    have total := total + 1
+    total += 2
+    info total;
+}
+info total;
+";
+
+#[test]
+fn initialize() {
+    let (client, result) = Client::start();
+    assert_eq!(result["serverInfo"]["name"], "azura-lsp");
+    let capabilities = &result["capabilities"];
+    assert_eq!(capabilities["textDocumentSync"], 2);
+    for provider in [
+        "hoverProvider",
+        "definitionProvider",
+        "referencesProvider",
+        "documentSymbolProvider",
+    ] {
+        assert_eq!(capabilities[provider], true, "{provider}");
+    }
+    assert_eq!(
+        capabilities["semanticTokensProvider"]["legend"]["tokenTypes"][1],
+        "variable"
+    );
+    client.shutdown();
+}
+
+#[test]
+fn diagnostics_follow_changes() {
+    let (mut client, _) = Client::start();
+    open(&mut client, "info 1;\ninfo \"é\" + @;\n");
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics["uri"], URI);
+    assert_eq!(diagnostics["version"], 1);
+    assert_eq!(
+        diagnostics["diagnostics"],
+        json!([{
+            "range": range(1, 11, 12),
+            "severity": 1,
+            "source": "azura",
+            "message": "Unexpected character `@`",
+        }])
+    );
+
+    // Replacing `@` with `2` fixes the error, and forgetting the `;` makes a new one
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": {"uri": URI, "version": 2},
+            "contentChanges": [
+                {"range": range(1, 11, 12), "text": "2"},
+                {"range": range(0, 6, 7), "text": ""},
+            ],
+        }),
+    );
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics["version"], 2);
+    assert_eq!(diagnostics["diagnostics"].as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics["diagnostics"][0]["range"], range(1, 0, 4));
+    assert!(diagnostics["diagnostics"][0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Expected ';' after value!"));
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": {"uri": URI, "version": 3},
+            "contentChanges": [{"text": "info 1;"}],
+        }),
+    );
+    assert_eq!(client.diagnostics()["diagnostics"], json!([]));
+
+    client.notify(
+        "textDocument/didClose",
+        json!({"textDocument": {"uri": URI}}),
+    );
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics["diagnostics"], json!([]));
+    assert_eq!(diagnostics["version"], Value::Null);
+    client.shutdown();
+}
+
+#[test]
+fn navigation() {
+    let (mut client, _) = Client::start();
+    open(&mut client, SOURCE);
+    // The `total` in the initializer of the local is the local itself, like in the compiler
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics["diagnostics"][0]["range"], range(2, 18, 23));
+    let local = json!({"uri": URI, "range": range(2, 9, 14)});
+    assert_eq!(client.at("textDocument/definition", 3, 5), local);
+    assert_eq!(client.at("textDocument/definition", 2, 18), local);
+    assert_eq!(
+        client.at("textDocument/definition", 6, 5),
+        json!({"uri": URI, "range": range(0, 5, 10)})
+    );
+    assert_eq!(client.at("textDocument/definition", 1, 0), Value::Null);
+
+    let references = client.at("textDocument/references", 4, 9);
+    let ranges: Vec<&Value> = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| &location["range"])
+        .collect();
+    assert_eq!(
+        ranges,
+        [
+            &range(2, 9, 14),
+            &range(2, 18, 23),
+            &range(3, 4, 9),
+            &range(4, 9, 14)
+        ]
+    );
+    let references = client.at("textDocument/references", 0, 7);
+    assert_eq!(references.as_array().unwrap().len(), 2);
+
+    let hover = client.at("textDocument/hover", 6, 7);
+    assert_eq!(hover["range"], range(6, 5, 10));
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("have total"), "{text}");
+    assert!(
+        text.contains("Global variable, declared on line 1"),
+        "{text}"
+    );
+    let hover = client.at("textDocument/hover", 4, 10);
+    assert!(hover["contents"]["value"]
+        .as_str()
+        .unwrap()
+        .contains("Local variable, declared on line 3"));
+    assert_eq!(client.at("textDocument/hover", 0, 14), Value::Null);
+    client.shutdown();
+}
+
+#[test]
+fn symbols_and_semantic_tokens() {
+    let (mut client, _) = Client::start();
+    open(&mut client, SOURCE);
+    let document = json!({"textDocument": {"uri": URI}});
+
+    let symbols = client.request("textDocument/documentSymbol", document.clone());
+    let symbols: Vec<(&Value, &Value)> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| (&symbol["name"], &symbol["detail"]))
+        .collect();
+    assert_eq!(
+        symbols,
+        [
+            (&json!("total"), &json!("global")),
+            (&json!("total"), &json!("local"))
+        ]
+    );
+
+    let tokens = client.request("textDocument/semanticTokens/full", document);
+    let data: Vec<u64> = tokens["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value.as_u64().unwrap())
+        .collect();
+    // Every token is [delta line, delta start, length, type, modifiers]
+    assert_eq!(
+        data[..20],
+        [
+            0, 0, 4, 0, 0, // have
+            0, 5, 5, 1, 3, // total, a global declaration
+            0, 6, 2, 8, 0, // :=
+            0, 3, 1, 7, 0, // 1
+        ]
+    );
+    // The `total` of `total += 2` is the local
+    assert!(data.chunks(5).any(|token| token == [1, 4, 5, 1, 0]));
+    client.shutdown();
+}
+
+#[test]
+fn unknown_requests_are_errors() {
+    let (mut client, _) = Client::start();
+    let response = client.call("textDocument/unknown", json!({}));
+    assert_eq!(response["error"]["code"], -32601);
+    let response = client.call("textDocument/hover", json!({"position": 1}));
+    assert_eq!(response["error"]["code"], -32602);
+    client.shutdown();
+}
@@ -0,0 +1,71 @@
+//! Any input has to end in tokens and diagnostics, never in a panic. The fuzz targets in `fuzz/`
+//! check the same things with coverage guided inputs.
+use std::io;
+
+use azura::{
+    compiler::compile,
+    keywords::KEYWORDS,
+    resolver::resolve,
+    scanner::{IncrementalScanner, Scanner, StreamScanner, TextEdit},
+    vm::Vm,
+};
+use proptest::prelude::*;
+
+/// Text biased towards what starts literals, comments and pragmas, with multi-byte characters
+fn source() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "([ \t\n/*a-z0-9_.'\"#!\\\\{}()\\[\\];:=<>+%&|^?~-]|é|λ|😀|\u{301}|r#|b'|0x|1e|\"\"\"){0,48}",
+        any::<String>(),
+    ]
+}
+
+fn run_all(source: &str) {
+    for result in Scanner::new(source).with_trivia() {
+        if let Err(error) = result {
+            let _ = error.to_string();
+        }
+    }
+    Scanner::new(source).with_keywords(&KEYWORDS).for_each(drop);
+    resolve(source);
+    match compile(source) {
+        Ok(_) => {
+            if let Err(error) = Vm::new(io::sink()).interpret(source) {
+                let _ = error.to_string();
+            }
+        }
+        Err(errors) => errors.iter().for_each(|error| drop(error.to_string())),
+    }
+}
+
+proptest! {
+    #[test]
+    fn nothing_panics(source in source()) {
+        run_all(&source);
+    }
+
+    #[test]
+    fn streams_of_any_bytes(bytes in prop::collection::vec(any::<u8>(), 0..64), split in 0usize..64) {
+        let split = split.min(bytes.len());
+        let chunks = [&bytes[..split], &bytes[split..]];
+        StreamScanner::from_chunks(chunks).for_each(drop);
+    }
+
+    #[test]
+    fn edits_of_any_text(source in source(), at in 0.0..=1.0f64, text in source()) {
+        let mut scanner = IncrementalScanner::new(source);
+        let mut start = (scanner.source().len() as f64 * at) as usize;
+        while !scanner.source().is_char_boundary(start) {
+            start -= 1;
+        }
+        scanner.edit(TextEdit { range: start..start, text: &text });
+    }
+}
+
+#[test]
+fn seed_corpus() {
+    run_all(include_str!("../../test.az"));
+    for seed in std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz/seeds")).unwrap() {
+        let bytes = std::fs::read(seed.unwrap().path()).unwrap();
+        run_all(&String::from_utf8_lossy(&bytes));
+    }
+}
@@ -0,0 +1,152 @@
+use azura::{
+    compiler::CompileError,
+    scanner::{LiteralError, Scanner, ScannerErrorKind, TokenKind},
+};
+
+mod common;
+
+use common::kinds;
+
+use TokenKind::*;
+
+#[test]
+#[allow(clippy::approx_constant)]
+fn fraction() {
+    assert_eq!(kinds("3.14"), [Float(3.14)]);
+    assert_eq!(kinds("0x1.8p1"), [Float(3.0)]);
+}
+
+#[test]
+fn digit_separators() {
+    assert_eq!(kinds("1_000_000"), [Integer(1_000_000)]);
+    assert_eq!(kinds("0xFF_FF"), [Integer(0xFFFF)]);
+    assert_eq!(kinds("0b1010_1010"), [Integer(0b1010_1010)]);
+    assert_eq!(kinds("1_0.2_5"), [Float(10.25)]);
+    assert_eq!(kinds("1_0e1_0"), [Float(1e11)]);
+}
+
+#[test]
+fn exponents() {
+    assert_eq!(kinds("6.02e23"), [Float(6.02e23)]);
+    assert_eq!(kinds("1E-9"), [Float(1e-9)]);
+    assert_eq!(kinds("1e+5"), [Float(1e5)]);
+    assert_eq!(kinds("1e3"), [Float(1000.0)]);
+}
+
+#[test]
+fn hex_floats() {
+    assert_eq!(kinds("0x1p-2"), [Float(0.25)]);
+    assert_eq!(kinds("0xA.8p0"), [Float(10.5)]);
+    assert_eq!(kinds("0x1.8P1"), [Float(3.0)]);
+}
+
+#[test]
+fn invalid_digit_is_pointed_at() {
+    for (source, pos) in [("0o19", 4), ("0o1_29", 6), ("x := 0b102", 10)] {
+        let error = Scanner::new(source).find_map(Result::err).unwrap();
+        assert_eq!(
+            error.kind,
+            ScannerErrorKind::IncorrectLiteral {
+                error: LiteralError::InvalidDigit {
+                    digit: source.chars().nth(pos - 1).unwrap(),
+                    radix: if source.contains("0o") { 8 } else { 2 },
+                }
+            },
+            "{source:?}"
+        );
+        assert_eq!((error.line, error.pos), (1, pos), "{source:?}");
+    }
+    let error = Scanner::new("0o19").find_map(Result::err).unwrap();
+    assert_eq!(
+        CompileError::from_scanner(&error).message,
+        "Failed to parse octal literal: digit `9` is not valid in an octal literal"
+    );
+}
+
+#[test]
+fn trailing_dot_is_a_float() {
+    assert_eq!(kinds("5."), [Float(5.0)]);
+    assert_eq!(kinds("5. + 1"), [Float(5.0), Add, Integer(1)]);
+    assert_eq!(kinds("(5.)"), [OPar, Float(5.0), CPar]);
+}
+
+#[test]
+fn range() {
+    assert_eq!(kinds("1..2"), [Integer(1), Range, Integer(2)]);
+    assert_eq!(kinds("1.5..2"), [Float(1.5), Range, Integer(2)]);
+    assert_eq!(kinds("0..=9"), [Integer(0), RangeInclusive, Integer(9)]);
+}
+
+#[test]
+fn exponent_after_dot_is_a_field() {
+    assert_eq!(kinds("1.e3"), [Integer(1), Dot, Ident("e3")]);
+    assert_eq!(kinds("1.0e3"), [Float(1000.0)]);
+}
+
+#[test]
+fn method_calls() {
+    assert_eq!(
+        kinds("5.abs()"),
+        [Integer(5), Dot, Ident("abs"), OPar, CPar]
+    );
+    assert_eq!(kinds("5._x"), [Integer(5), Dot, Ident("_x")]);
+    assert_eq!(kinds("0x10.method"), [Integer(16), Dot, Ident("method")]);
+    assert_eq!(kinds("0x10.face"), [Integer(16), Dot, Ident("face")]);
+    assert_eq!(kinds("0b1010.a"), [Integer(10), Dot, Ident("a")]);
+    assert_eq!(kinds("0o17.len"), [Integer(15), Dot, Ident("len")]);
+}
+
+#[test]
+fn leading_dot_is_not_a_number() {
+    assert_eq!(kinds(".5"), [Dot, Integer(5)]);
+}
+
+#[test]
+fn field_index() {
+    assert_eq!(kinds("x.0"), [Ident("x"), Dot, Integer(0)]);
+    assert_eq!(
+        kinds("x.0.1"),
+        [Ident("x"), Dot, Integer(0), Dot, Integer(1)]
+    );
+    assert_eq!(
+        kinds("x.1.5.abs()"),
+        [
+            Ident("x"),
+            Dot,
+            Integer(1),
+            Dot,
+            Integer(5),
+            Dot,
+            Ident("abs"),
+            OPar,
+            CPar
+        ]
+    );
+    assert_eq!(kinds("x. /* c */ 0"), [Ident("x"), Dot, Integer(0)]);
+    assert_eq!(
+        kinds("x?.0.1"),
+        [Ident("x"), OptionalDot, Integer(0), Dot, Integer(1)]
+    );
+    // Only the number right after the dot is an index
+    assert_eq!(
+        kinds("x.0 + 1.5"),
+        [Ident("x"), Dot, Integer(0), Add, Float(1.5)]
+    );
+}
+
+#[test]
+fn field_index_is_decimal() {
+    let error = Scanner::new("x.0x1")
+        .find_map(Result::err)
+        .expect("a hexadecimal index is an error");
+    assert_eq!(
+        error.kind,
+        ScannerErrorKind::IncorrectLiteral {
+            error: LiteralError::InvalidDigit {
+                digit: 'x',
+                radix: 10
+            }
+        }
+    );
+    assert_eq!(error.pos, 4);
+}
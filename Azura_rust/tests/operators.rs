@@ -0,0 +1,150 @@
+use azura::scanner::TokenKind;
+
+mod common;
+
+use common::kinds;
+
+use TokenKind::*;
+
+/// Every punctuation token along with its spelling
+const OPERATORS: &[(&str, TokenKind)] = &[
+    ("(", OPar),
+    (")", CPar),
+    ("{", OBrace),
+    ("}", CBrace),
+    ("[", OBracket),
+    ("]", CBracket),
+    (",", Comma),
+    (".", Dot),
+    (";", Semicolon),
+    (":", Colon),
+    ("#", Hashtag),
+    ("?", Question),
+    ("~", BitNot),
+    (":=", Walrus),
+    ("/", Div),
+    ("/=", DivAssign),
+    ("*", Mul),
+    ("*=", MulAssign),
+    ("-", Sub),
+    ("-=", SubAssign),
+    ("+", Add),
+    ("+=", AddAssign),
+    ("==", Equal),
+    ("!=", NotEqual),
+    ("!", Bang),
+    ("=", Reassignment),
+    (">", Greater),
+    (">=", GreaterOrEqual),
+    ("<", Less),
+    ("<=", LessOrEqual),
+    ("|", BitOr),
+    ("|=", BitOrAssign),
+    ("^", BitXor),
+    ("^=", BitXorAssign),
+    ("%", Rem),
+    ("%=", RemAssign),
+    ("&", BitAnd),
+    ("&=", BitAndAssign),
+    ("<<", LeftShift),
+    ("<<=", LeftShiftAssign),
+    (">>", RightShift),
+    (">>=", RightShiftAssign),
+    ("->", Arrow),
+    ("=>", FatArrow),
+    ("&&", LogicalAnd),
+    ("&&=", LogicalAndAssign),
+    ("||", LogicalOr),
+    ("||=", LogicalOrAssign),
+    ("..", Range),
+    ("..=", RangeInclusive),
+    ("??", NullCoalesce),
+    ("??=", NullCoalesceAssign),
+    ("?.", OptionalDot),
+    ("**", Pow),
+    ("**=", PowAssign),
+];
+
+#[test]
+fn every_operator() {
+    for (spelling, kind) in OPERATORS {
+        assert_eq!(kinds(spelling), std::slice::from_ref(kind), "{spelling:?}");
+        assert_eq!(kind.to_string(), *spelling);
+        // Operators are not affected by what surrounds them
+        let source = format!("a{spelling}b");
+        assert_eq!(
+            kinds(&source),
+            [Ident("a"), kind.clone(), Ident("b")],
+            "{source:?}"
+        );
+    }
+}
+
+#[test]
+fn every_pair_of_operators() {
+    // Two operators separated by a space always scan as themselves, no matter how they would
+    // combine without it
+    for (first, first_kind) in OPERATORS {
+        for (second, second_kind) in OPERATORS {
+            let source = format!("{first} {second}");
+            assert_eq!(
+                kinds(&source),
+                [first_kind.clone(), second_kind.clone()],
+                "{source:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn longest_match() {
+    for (source, expected) in [
+        ("&&&", [LogicalAnd, BitAnd].as_slice()),
+        ("&&&&", &[LogicalAnd, LogicalAnd]),
+        ("|||=", &[LogicalOr, BitOrAssign]),
+        ("***", &[Pow, Mul]),
+        ("**==", &[PowAssign, Reassignment]),
+        ("...", &[Range, Dot]),
+        ("..==", &[RangeInclusive, Reassignment]),
+        ("???", &[NullCoalesce, Question]),
+        ("??.", &[NullCoalesce, Dot]),
+        ("?..", &[OptionalDot, Dot]),
+        ("-->", &[Sub, Arrow]),
+        ("->>", &[Arrow, Greater]),
+        ("==>", &[Equal, Greater]),
+        ("=>=", &[FatArrow, Reassignment]),
+        ("<<==", &[LeftShiftAssign, Reassignment]),
+        ("~~", &[BitNot, BitNot]),
+        ("[[]]", &[OBracket, OBracket, CBracket, CBracket]),
+    ] {
+        assert_eq!(kinds(source), expected, "{source:?}");
+    }
+}
+
+#[test]
+fn operators_in_context() {
+    assert_eq!(
+        kinds("x ?? [1, 2][0]"),
+        [
+            Ident("x"),
+            NullCoalesce,
+            OBracket,
+            Integer(1),
+            Comma,
+            Integer(2),
+            CBracket,
+            OBracket,
+            Integer(0),
+            CBracket
+        ]
+    );
+    assert_eq!(
+        kinds("a.b?.c"),
+        [Ident("a"), Dot, Ident("b"), OptionalDot, Ident("c")]
+    );
+    assert_eq!(kinds("2**-1"), [Integer(2), Pow, Sub, Integer(1)]);
+    assert_eq!(
+        kinds("x?1:2"),
+        [Ident("x"), Question, Integer(1), Colon, Integer(2)]
+    );
+}
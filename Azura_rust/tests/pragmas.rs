@@ -0,0 +1,155 @@
+use azura::{
+    compiler::compile,
+    keywords::Edition,
+    pragmas::{Lint, Pragma, PragmaError, Pragmas},
+    scanner::{Scanner, TokenKind},
+    vm::Vm,
+};
+
+mod common;
+
+use common::kinds;
+
+/// The messages of all compile errors in `source`
+fn errors(source: &str) -> Vec<String> {
+    match compile(source) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|error| error.message).collect(),
+    }
+}
+
+fn output(source: &str) -> String {
+    let mut vm = Vm::new(Vec::new());
+    vm.interpret(source).unwrap();
+    String::from_utf8(vm.into_output()).unwrap()
+}
+
+use TokenKind::*;
+
+#[test]
+fn shebang_is_skipped() {
+    assert_eq!(kinds("#!/usr/bin/env azura\ninfo"), [Ident("info")]);
+    assert_eq!(output("#!/usr/bin/env azura\ninfo 1;"), "1\n");
+    let trivia: Vec<_> = Scanner::new("#!/usr/bin/env azura\nx")
+        .with_trivia()
+        .map(|token| token.unwrap().kind)
+        .collect();
+    assert_eq!(
+        trivia,
+        [Shebang("/usr/bin/env azura"), Newline, Ident("x"), Eof]
+    );
+    assert_eq!(Shebang("/bin/azura").to_string(), "#!/bin/azura");
+}
+
+#[test]
+fn shebang_only_at_the_start() {
+    assert_eq!(kinds(" #!x"), [Hashtag, Bang, Ident("x")]);
+    assert_eq!(kinds("\n#!x"), [Hashtag, Bang, Ident("x")]);
+}
+
+#[test]
+fn pragmas_start_their_line() {
+    assert_eq!(
+        kinds("#edition 2 // the latest\n  #strict\nx"),
+        [Pragma("edition 2"), Pragma("strict"), Ident("x")]
+    );
+    assert_eq!(kinds("#allow(unused)"), [Pragma("allow(unused)")]);
+    // Anywhere else `#` stays a token of its own
+    assert_eq!(kinds("x #strict"), [Ident("x"), Hashtag, Ident("strict")]);
+    assert_eq!(kinds("r#x"), [Ident("r"), Hashtag, Ident("x")]);
+    assert_eq!(kinds("# strict"), [Hashtag, Ident("strict")]);
+    assert_eq!(kinds("x\n#strict"), [Ident("x"), Pragma("strict")]);
+}
+
+#[test]
+fn parsing() {
+    assert_eq!(Pragma::parse("edition 2"), Ok(Pragma::Edition(Edition::V2)));
+    assert_eq!(Pragma::parse(" strict "), Ok(Pragma::Strict));
+    assert_eq!(
+        Pragma::parse("allow( unused, unused )"),
+        Ok(Pragma::Allow(vec![Lint::Unused, Lint::Unused]))
+    );
+    assert_eq!(Pragma::parse("allow()"), Ok(Pragma::Allow(Vec::new())));
+    assert_eq!(
+        Pragma::parse("edition 3"),
+        Err(PragmaError::UnknownEdition {
+            edition: "3".to_owned()
+        })
+    );
+    assert_eq!(
+        Pragma::parse("allow(everything)"),
+        Err(PragmaError::UnknownLint {
+            lint: "everything".to_owned()
+        })
+    );
+    assert!(matches!(
+        Pragma::parse("allow unused"),
+        Err(PragmaError::Malformed { name: "allow", .. })
+    ));
+    assert!(matches!(
+        Pragma::parse("strict mode"),
+        Err(PragmaError::Malformed { name: "strict", .. })
+    ));
+    assert_eq!(
+        Pragma::parse("fast"),
+        Err(PragmaError::Unknown {
+            name: "fast".to_owned()
+        })
+    );
+
+    let mut pragmas = Pragmas::default();
+    assert!(!pragmas.denies(Lint::Unused));
+    pragmas.apply(Pragma::Strict);
+    assert!(pragmas.denies(Lint::Unused));
+    pragmas.apply(Pragma::Allow(vec![Lint::Unused]));
+    assert!(!pragmas.denies(Lint::Unused));
+}
+
+#[test]
+fn edition_decides_the_keywords() {
+    assert_eq!(errors("have switch := 1"), ["Expected a variable name!"]);
+    assert_eq!(
+        errors("#edition 2\nhave switch := 1"),
+        ["Expected a variable name!"]
+    );
+    assert_eq!(output("#edition 1\nhave switch := 1\ninfo switch;"), "1\n");
+}
+
+#[test]
+fn strict_reports_unused_locals() {
+    let source = "{ have used := 1 have unused := 2 have _ignored := 3 info used; }";
+    assert_eq!(errors(source), Vec::<String>::new());
+    assert_eq!(
+        errors(&format!("#strict\n{source}")),
+        ["Unused local variable 'unused'!"]
+    );
+    assert_eq!(
+        errors(&format!("#strict\n#allow(unused)\n{source}")),
+        Vec::<String>::new()
+    );
+    // Globals might still be used by a later line of the REPL
+    assert_eq!(errors("#strict\nhave x := 1"), Vec::<String>::new());
+    let error = &compile("#strict\n{\n  have x := 1\n}").unwrap_err()[0];
+    assert_eq!(
+        error.to_string(),
+        "[line 3] Error at 'x': Unused local variable 'x'!"
+    );
+}
+
+#[test]
+fn invalid_pragmas() {
+    assert_eq!(errors("#fast"), ["Unknown pragma 'fast'."]);
+    assert_eq!(
+        errors("#edition 9"),
+        ["Unknown edition '9', expected a number from 1 to 2."]
+    );
+    assert_eq!(
+        errors("info 1;\n#strict"),
+        ["Pragmas must come before any code!"]
+    );
+    let error = &compile("#!/usr/bin/env azura\n\n#allow(all)").unwrap_err()[0];
+    assert_eq!(
+        error.to_string(),
+        "[line 3] Error at '#allow(all)': Unknown lint 'all'."
+    );
+}
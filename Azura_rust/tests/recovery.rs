@@ -0,0 +1,71 @@
+use azura::scanner::{Scanner, ScannerErrorKind, TokenKind};
+
+/// Every token kind in `source`, with errors written as `Err` and their kind
+fn scanned(source: &str) -> Vec<Result<TokenKind<'_>, ScannerErrorKind<'_>>> {
+    Scanner::new(source)
+        .map(|token| token.map(|token| token.kind).map_err(|error| error.kind))
+        .collect()
+}
+
+#[test]
+fn unexpected_characters() {
+    for character in ['@', '$', '`'] {
+        let source = format!("x {character} y");
+        let error = Scanner::new(&source).find_map(Result::err).unwrap();
+        assert_eq!(
+            error.kind,
+            ScannerErrorKind::UnexpectedCharacter { character }
+        );
+        assert_eq!((error.line, error.pos), (1, 3));
+        assert_eq!(error.lexeme, character.to_string());
+        assert_eq!(
+            scanned(&source),
+            [
+                Ok(TokenKind::Ident("x")),
+                Err(ScannerErrorKind::UnexpectedCharacter { character }),
+                Ok(TokenKind::Ident("y")),
+                Ok(TokenKind::Eof),
+            ]
+        );
+    }
+}
+
+#[test]
+fn unterminated_string_recovers_at_the_end_of_the_line() {
+    let mut tokens = scanned("info \"abc\ninfo 1;").into_iter();
+    assert_eq!(tokens.next(), Some(Ok(TokenKind::Ident("info"))));
+    assert!(matches!(
+        tokens.next(),
+        Some(Err(ScannerErrorKind::Unmatched { .. }))
+    ));
+    assert_eq!(
+        tokens.collect::<Vec<_>>(),
+        [
+            Ok(TokenKind::Ident("info")),
+            Ok(TokenKind::Integer(1)),
+            Ok(TokenKind::Semicolon),
+            Ok(TokenKind::Eof),
+        ]
+    );
+}
+
+#[test]
+fn several_errors_in_a_row() {
+    let errors: Vec<_> = Scanner::new("@$ 0x `\n'ab")
+        .filter_map(Result::err)
+        .map(|error| (error.line, error.pos))
+        .collect();
+    assert_eq!(errors, [(1, 1), (1, 2), (1, 4), (1, 7), (2, 1)]);
+}
+
+#[test]
+fn unterminated_block_comment_ends_the_input() {
+    assert_eq!(
+        scanned("x /* y\nz"),
+        [
+            Ok(TokenKind::Ident("x")),
+            Err(ScannerErrorKind::UnterminatedBlockComment),
+            Ok(TokenKind::Eof),
+        ]
+    );
+}
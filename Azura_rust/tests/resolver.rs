@@ -0,0 +1,85 @@
+use azura::resolver::{resolve, Access, Resolution, Scope};
+
+/// Every reference as its line, its access and the line its declaration is on
+fn references(resolution: &Resolution) -> Vec<(usize, Access, Option<usize>)> {
+    resolution
+        .references
+        .iter()
+        .map(|reference| {
+            let declaration = reference
+                .declaration
+                .map(|idx| resolution.declarations[idx].token.line);
+            (reference.token.line, reference.access, declaration)
+        })
+        .collect()
+}
+
+#[test]
+fn locals_shadow_globals() {
+    let resolution = resolve(
+        "have x := 1
+{
+    have x := x
+    {
+        x = 2
+    }
+}
+info x;",
+    );
+    let scopes: Vec<Scope> = resolution
+        .declarations
+        .iter()
+        .map(|declaration| declaration.scope)
+        .collect();
+    assert_eq!(scopes, [Scope::Global, Scope::Local { depth: 1 }]);
+    assert_eq!(
+        references(&resolution),
+        [
+            // Like the compiler, the local is in scope in its own initializer
+            (3, Access::Read, Some(3)),
+            (5, Access::Write, Some(3)),
+            (8, Access::Read, Some(1)),
+        ]
+    );
+    assert_eq!(resolution.references[1].scope, Scope::Local { depth: 1 });
+    let local = &resolution.declarations[1];
+    assert_eq!(
+        &"have x := 1\n{\n    have x := x\n    {\n        x = 2\n    }\n}"
+            [local.visible.start..local.visible.end],
+        "x := x\n    {\n        x = 2\n    }\n}"
+    );
+}
+
+#[test]
+fn globals_are_resolved_by_name() {
+    let resolution = resolve("info a;\nhave a := 1\nhave a := a\na += b");
+    assert_eq!(
+        references(&resolution),
+        [
+            // Used before it is declared, as in a function
+            (1, Access::Read, Some(2)),
+            (3, Access::Read, Some(2)),
+            (4, Access::Write, Some(3)),
+            (4, Access::Read, None),
+        ]
+    );
+    assert_eq!(resolution.references_to(0).count(), 3);
+    assert_eq!(resolution.same_variable(1).count(), 2);
+    assert_eq!(resolution.declaration_at(13), Some(0));
+    assert_eq!(resolution.declaration_at(30), Some(0));
+    assert_eq!(resolution.declaration_at(5), Some(0));
+}
+
+#[test]
+fn errors_are_skipped() {
+    let resolution = resolve("have := @ x {\n have y = 1\n info y + ;\n} } y\n{ x");
+    assert_eq!(
+        references(&resolution),
+        [
+            (1, Access::Read, None),
+            (3, Access::Read, Some(2)),
+            (4, Access::Read, None),
+            (5, Access::Read, None),
+        ]
+    );
+}
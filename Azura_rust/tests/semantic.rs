@@ -0,0 +1,84 @@
+use azura::semantic::{classify, Category};
+
+/// Every classified token as its lexeme, its category and whether it is a declaration
+fn classified(source: &str) -> Vec<(&str, Category, bool)> {
+    classify(source)
+        .into_iter()
+        .map(|token| (token.token.lexeme, token.category, token.declaration))
+        .collect()
+}
+
+#[test]
+fn tokens_are_classified() {
+    use Category::*;
+    assert_eq!(
+        classified("#edition 1\nhave s := \"a\" + 'b' // c\ninfo -s <= 1.5 or nil;"),
+        [
+            ("#edition 1", Keyword, false),
+            ("have", Keyword, false),
+            ("s", GlobalVariable, true),
+            (":=", Operator, false),
+            ("\"a\"", String, false),
+            ("+", Operator, false),
+            ("'b'", String, false),
+            ("// c", Comment, false),
+            ("info", Keyword, false),
+            ("-", Operator, false),
+            ("s", GlobalVariable, false),
+            ("<=", Operator, false),
+            ("1.5", Number, false),
+            ("or", Keyword, false),
+            ("nil", Keyword, false),
+        ]
+    );
+}
+
+#[test]
+fn variables_follow_their_scope() {
+    let variables: Vec<(usize, Category, bool)> = classify(
+        "have x := 1
+{
+    have y := x
+    {
+        have x := y
+        x = x + y
+    }
+    info x;
+}
+info z;",
+    )
+    .into_iter()
+    .filter(|token| ["x", "y", "z"].contains(&token.token.lexeme))
+    .map(|token| (token.token.line, token.category, token.declaration))
+    .collect();
+    use Category::*;
+    assert_eq!(
+        variables,
+        [
+            (1, GlobalVariable, true),
+            (3, LocalVariable, true),
+            (3, GlobalVariable, false),
+            (5, LocalVariable, true),
+            (5, LocalVariable, false),
+            (6, LocalVariable, false),
+            (6, LocalVariable, false),
+            (6, LocalVariable, false),
+            (8, GlobalVariable, false),
+            // Never declared, which is only found out when the program runs
+            (10, GlobalVariable, false),
+        ]
+    );
+}
+
+#[test]
+fn unfinished_code_is_classified() {
+    let categories: Vec<Category> = classify("{ have a := @ a +")
+        .into_iter()
+        .map(|token| token.category)
+        .collect();
+    use Category::*;
+    assert_eq!(
+        categories,
+        [Keyword, LocalVariable, Operator, LocalVariable, Operator]
+    );
+}
@@ -0,0 +1,190 @@
+use std::io::{self, Read};
+
+use azura::scanner::{OwnedToken, Scanner, StreamError, StreamScanner};
+use proptest::prelude::*;
+
+/// The tokens and errors of scanning all of `source` at once
+fn whole(source: &str, trivia: bool) -> Vec<Result<OwnedToken, String>> {
+    let scanner = Scanner::new(source);
+    let scanner: Box<dyn Iterator<Item = _>> = if trivia {
+        Box::new(scanner.with_trivia())
+    } else {
+        Box::new(scanner)
+    };
+    scanner
+        .map(|token| {
+            token
+                .map(|token| token.into_owned())
+                .map_err(|error| format!("{:?}", error.into_owned()))
+        })
+        .collect()
+}
+
+/// The tokens and errors of scanning `source` in chunks of `size` bytes
+fn streamed(source: &str, size: usize, trivia: bool) -> Vec<Result<OwnedToken, String>> {
+    let scanner = StreamScanner::from_chunks(source.as_bytes().chunks(size));
+    let scanner = if trivia {
+        scanner.with_trivia()
+    } else {
+        scanner
+    };
+    scanner
+        .map(|token| {
+            token.map_err(|error| match error {
+                StreamError::Scanner(error) => format!("{error:?}"),
+                StreamError::Io(error) => panic!("unexpected IO error {error}"),
+            })
+        })
+        .collect()
+}
+
+const SOURCES: &[&str] = &[
+    include_str!("../../test.az"),
+    "#!/usr/bin/env azura\n#edition 2\nhave x := 1\ninfo x.0 + 1.5e3;",
+    "have s := \"\"\"\n    multi\n    line\n    \"\"\"\ninfo s; // done",
+    "/* a /* nested */ comment */ r##\"raw \"# string\"## b'\\xff' 'é' 0x_ff",
+    "\"unterminated\nx := 'ab' \"\\q\" 1e\n/* open",
+    "x ?? [1, 2]?.0 ..= 3 **= 4",
+    "a\r\nb\n\n\n   c",
+];
+
+#[test]
+fn chunks_do_not_change_tokens() {
+    for source in SOURCES {
+        for trivia in [false, true] {
+            let expected = whole(source, trivia);
+            for size in 1..=9 {
+                assert_eq!(
+                    streamed(source, size, trivia),
+                    expected,
+                    "{source:?} in chunks of {size}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn unterminated_string_gives_the_same_tokens() {
+    let source = "\"unterminated\nx := 'ab' \"\\q\" 1e\n/* open";
+    assert_eq!(streamed(source, 1, false), whole(source, false));
+}
+
+proptest! {
+    #[test]
+    fn any_split_gives_the_same_tokens(
+        source in "[ \t\r\n/*a-z0-9_.'\"@#$(){}\\[\\];:,=<>!?~+%&|^éλ-]{0,64}",
+        size in 1..16usize,
+        trivia in any::<bool>(),
+    ) {
+        prop_assert_eq!(streamed(&source, size, trivia), whole(&source, trivia));
+    }
+}
+
+#[test]
+fn strings_past_the_lookahead_differ_from_whole_scans() {
+    let string = |lines| format!("\"{}\"", "a\n".repeat(lines));
+    // Closed within 64 KiB, the same as scanning all of it at once
+    let source = string(1_000);
+    assert_eq!(streamed(&source, 1024, false), whole(&source, false));
+    // Closed only after 80 KB, which the whole scan sees but the stream does not look for
+    let source = string(40_000);
+    let expected = whole(&source, false);
+    assert!(matches!(expected[..], [Ok(_), Ok(_)]), "{expected:?}");
+    let tokens = streamed(&source, 1024, false);
+    assert!(tokens[0].is_err(), "{:?}", tokens[0]);
+    assert_ne!(tokens, expected);
+}
+
+/// Gives out the input a few bytes at a time, like a pipe
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.0.len()).min(3);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
+}
+
+#[test]
+fn readers() {
+    let source = "have λ := \"ü\"\ninfo λ;";
+    let expected = whole(source, false);
+    let tokens: Vec<_> = StreamScanner::from_reader(Trickle(source.as_bytes()))
+        .map(|token| token.map_err(|error| error.to_string()))
+        .collect();
+    assert_eq!(tokens, expected);
+    let tokens: Vec<_> = StreamScanner::from_reader(source.as_bytes())
+        .map(|token| token.map_err(|error| error.to_string()))
+        .collect();
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn invalid_utf8_is_an_io_error() {
+    for input in [b"x \xff y".as_slice(), b"x \xc3"] {
+        let error = StreamScanner::from_chunks([input])
+            .find_map(Result::err)
+            .unwrap();
+        assert!(
+            matches!(&error, StreamError::Io(error) if error.kind() == io::ErrorKind::InvalidData),
+            "{error:?}"
+        );
+    }
+}
+
+/// Repeats a line of code `lines` times, one line per read
+struct Repeat {
+    lines: usize,
+}
+
+impl Read for Repeat {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        const LINE: &[u8] = b"have x := x + 1 // counting\n";
+        if self.lines == 0 {
+            return Ok(0);
+        }
+        self.lines -= 1;
+        buf[..LINE.len()].copy_from_slice(LINE);
+        Ok(LINE.len())
+    }
+}
+
+#[test]
+fn memory_stays_bounded() {
+    let lines = 200_000;
+    let mut scanner = StreamScanner::from_reader(Repeat { lines });
+    let mut tokens = 0;
+    let mut most = 0;
+    while let Some(token) = scanner.next() {
+        token.unwrap();
+        tokens += 1;
+        most = most.max(scanner.buffered());
+    }
+    // Six tokens per line, and the end of file
+    assert_eq!(tokens, lines * 6 + 1);
+    assert!(most < 1024, "held {most} bytes at once");
+}
+
+#[test]
+fn unterminated_string_does_not_buffer_the_rest() {
+    let lines = std::iter::once("\"abc\n").chain(std::iter::repeat_n("have x := 1 + 2\n", 20_000));
+    let mut scanner = StreamScanner::from_chunks(lines);
+    assert!(matches!(scanner.next(), Some(Err(StreamError::Scanner(_)))));
+    // The closing quote is only looked for in the next 64 KiB, not in all of the 320 KB
+    let most = 65 * 1024;
+    assert!(
+        scanner.buffered() < most,
+        "held {} bytes after the string",
+        scanner.buffered()
+    );
+    let mut tokens = 0;
+    while let Some(token) = scanner.next() {
+        token.unwrap();
+        tokens += 1;
+        assert!(scanner.buffered() < most);
+    }
+    assert_eq!(tokens, 20_000 * 6 + 1);
+}
@@ -0,0 +1,164 @@
+use azura::scanner::{Scanner, ScannerErrorKind, StrStyle, Token, TokenKind};
+
+mod common;
+
+use common::tokens;
+
+/// The value of the only string in `source`
+fn value(source: &str) -> String {
+    let tokens = tokens(source);
+    let [Token {
+        kind: TokenKind::Str(string),
+        ..
+    }, Token {
+        kind: TokenKind::Eof,
+        ..
+    }] = tokens.as_slice()
+    else {
+        panic!("expected a single string, got {tokens:?}");
+    };
+    string.value().into_owned()
+}
+
+fn style(source: &str) -> StrStyle {
+    match tokens(source)[0].kind {
+        TokenKind::Str(string) => string.style,
+        ref other => panic!("expected a string, got {other:?}"),
+    }
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(value(r#""a\nb\tc\\d\"e\'f\0""#), "a\nb\tc\\d\"e'f\0");
+    assert_eq!(value(r#""\u{41}\u{1F600}""#), "A😀");
+    assert_eq!(value(r#""it's""#), "it's");
+    assert_eq!(value("\"no escapes\""), "no escapes");
+}
+
+#[test]
+fn line_continuation() {
+    assert_eq!(value("\"one \\\n    two\""), "one two");
+    assert_eq!(value("\"one \\\r\n\ttwo\""), "one two");
+}
+
+#[test]
+fn invalid_escapes() {
+    for (source, sequence, pos) in [
+        (r#""a\qb""#, r"\q", 3),
+        (r#""\u{110000}""#, r"\u{110000}", 2),
+        (r#""\u{}""#, r"\u{", 2),
+        (r#""\u41""#, r"\u", 2),
+        ("\"\\\r\"", "\\\r", 2),
+    ] {
+        let error = Scanner::new(source)
+            .find_map(Result::err)
+            .unwrap_or_else(|| panic!("{source:?} should not scan"));
+        assert_eq!(
+            error.kind,
+            ScannerErrorKind::InvalidEscape { sequence },
+            "{source:?}"
+        );
+        assert_eq!(error.pos, pos, "{source:?}");
+        // The whole string is skipped
+        assert_eq!(error.lexeme, source);
+    }
+}
+
+#[test]
+fn raw_strings() {
+    assert_eq!(value(r#"r"C:\path\n""#), r"C:\path\n");
+    assert_eq!(value(r###"r#"say "hi""#"###), r#"say "hi""#);
+    assert_eq!(value(r####"r##"a "# b"##"####), r##"a "# b"##);
+    assert_eq!(value("r\"two\nlines\""), "two\nlines");
+    assert_eq!(style(r##"r#""#"##), StrStyle::Raw { hashes: 1 });
+}
+
+#[test]
+fn r_is_still_an_identifier() {
+    let kinds: Vec<_> = tokens("r rb r#x")
+        .into_iter()
+        .map(|token| token.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::Ident("r"),
+            TokenKind::Ident("rb"),
+            TokenKind::Ident("r"),
+            TokenKind::Hashtag,
+            TokenKind::Ident("x"),
+            TokenKind::Eof
+        ]
+    );
+}
+
+#[test]
+fn multi_line_strings() {
+    let source = "\"\"\"
+    Hello,
+      World!
+    \"\"\"";
+    assert_eq!(style(source), StrStyle::Multiline);
+    assert_eq!(value(source), "Hello,\n  World!");
+    // The closing quotes count towards the shared indentation
+    assert_eq!(value("\"\"\"\n    a\n  \"\"\""), "  a");
+    // Blank lines do not
+    assert_eq!(value("\"\"\"\n    a\n\n    b\n    \"\"\""), "a\n\nb");
+    assert_eq!(
+        value("\"\"\"\n  it's \"quoted\"\n  \"\"\""),
+        "it's \"quoted\""
+    );
+    assert_eq!(value("\"\"\"one line\"\"\""), "one line");
+    assert_eq!(value("\"\"\"\n  a \\\n  b\n  \"\"\""), "a b");
+    assert_eq!(value("\"\"\"\n  \\tx\n\"\"\""), "  \tx");
+}
+
+#[test]
+fn lines_after_literals() {
+    for source in [
+        "\"a\nb\nc\" x",
+        "r\"a\nb\nc\" x",
+        "\"\"\"\na\nb\"\"\" x",
+        "r#\"\n\n\"# x",
+    ] {
+        let tokens = tokens(source);
+        assert_eq!(tokens[0].line, 1, "{source:?}");
+        let x = &tokens[1];
+        assert_eq!(x.kind, TokenKind::Ident("x"), "{source:?}");
+        assert_eq!(
+            (x.line, x.pos),
+            (3, x.span.start - source.rfind('\n').unwrap())
+        );
+    }
+}
+
+#[test]
+fn escape_errors_point_at_their_line() {
+    let error = Scanner::new("\"\"\"\n  fine\n  not \\q fine\n\"\"\"")
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!((error.line, error.pos), (3, 7));
+}
+
+#[test]
+fn unterminated_strings() {
+    let source = "x := \"open\ny := 1";
+    let mut scanner = Scanner::new(source);
+    let error = scanner.find_map(Result::err).unwrap();
+    assert_eq!(error.message.as_deref(), Some("Unterminated string"));
+    // Scanning continues on the next line
+    let next = scanner.next().unwrap().unwrap();
+    assert_eq!((next.kind, next.line), (TokenKind::Ident("y"), 2));
+
+    for (source, message) in [
+        ("r#\"open\"\nx", "Unterminated raw string"),
+        ("\"\"\"open\n\"\"x", "Unterminated multi-line string"),
+    ] {
+        let mut scanner = Scanner::new(source);
+        let error = scanner.next().unwrap().unwrap_err();
+        assert_eq!(error.message.as_deref(), Some(message));
+        assert_eq!(error.lexeme, source);
+        let eof = scanner.next().unwrap().unwrap();
+        assert_eq!((eof.kind, eof.line), (TokenKind::Eof, 2));
+    }
+}
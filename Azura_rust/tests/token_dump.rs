@@ -0,0 +1,138 @@
+use std::{fs, path::PathBuf, process::Command};
+
+use azura::{
+    keywords::KEYWORDS,
+    scanner::{Scanner, TokenRecord},
+};
+use serde_json::{json, Value};
+
+fn records(source: &str) -> Vec<Value> {
+    Scanner::new(source)
+        .with_trivia()
+        .with_keywords(&KEYWORDS)
+        .map(|result| {
+            let record = match &result {
+                Ok(token) => TokenRecord::from(token),
+                Err(error) => TokenRecord::from(error),
+            };
+            serde_json::to_value(record).unwrap()
+        })
+        .collect()
+}
+
+/// Writes `source` to a file and runs `azura tokens` with `args` on it
+fn dump(name: &str, source: &str, args: &[&str]) -> (String, Option<i32>) {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_azura"))
+        .arg("tokens")
+        .args(args)
+        .arg(&path)
+        .output()
+        .unwrap();
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        output.status.code(),
+    )
+}
+
+#[test]
+fn records_follow_the_schema() {
+    assert_eq!(
+        records("have x := 1 // one\n"),
+        [
+            json!({"kind": "Keyword", "lexeme": "have", "line": 1, "column": 1, "start": 0, "end": 4, "value": "have"}),
+            json!({"kind": "Whitespace", "lexeme": " ", "line": 1, "column": 5, "start": 4, "end": 5, "value": " "}),
+            json!({"kind": "Ident", "lexeme": "x", "line": 1, "column": 6, "start": 5, "end": 6, "value": "x"}),
+            json!({"kind": "Whitespace", "lexeme": " ", "line": 1, "column": 7, "start": 6, "end": 7, "value": " "}),
+            json!({"kind": "Walrus", "lexeme": ":=", "line": 1, "column": 8, "start": 7, "end": 9}),
+            json!({"kind": "Whitespace", "lexeme": " ", "line": 1, "column": 10, "start": 9, "end": 10, "value": " "}),
+            json!({"kind": "Integer", "lexeme": "1", "line": 1, "column": 11, "start": 10, "end": 11, "value": 1}),
+            json!({"kind": "Whitespace", "lexeme": " ", "line": 1, "column": 12, "start": 11, "end": 12, "value": " "}),
+            json!({"kind": "LineComment", "lexeme": "// one", "line": 1, "column": 13, "start": 12, "end": 18, "value": " one"}),
+            json!({"kind": "Newline", "lexeme": "\n", "line": 1, "column": 19, "start": 18, "end": 19}),
+            json!({"kind": "Eof", "lexeme": "", "line": 2, "column": 1, "start": 19, "end": 19}),
+        ]
+    );
+}
+
+#[test]
+fn columns_count_bytes() {
+    let records = records("é x");
+    assert_eq!(records[2]["lexeme"], "x");
+    assert_eq!(records[2]["column"], 4);
+    assert_eq!(records[2]["start"], 3);
+}
+
+#[test]
+fn literal_values() {
+    let values: Vec<Value> =
+        records(r#""a\tb" r"\t" 'é' b'a' b"a\x00" 2.5 1_000 0x1_0000_0000_0000_0000"#)
+            .into_iter()
+            .filter(|record| record["kind"] != "Whitespace" && record["kind"] != "Eof")
+            .map(|record| json!([record["kind"], record["value"]]))
+            .collect();
+    assert_eq!(
+        values,
+        [
+            json!(["Str", "a\tb"]),
+            json!(["Str", "\\t"]),
+            json!(["Char", "é"]),
+            json!(["Byte", 97]),
+            json!(["ByteStr", [97, 0]]),
+            json!(["Float", 2.5]),
+            json!(["Integer", 1000]),
+            json!(["BigInteger", {"digits": "10000000000000000", "radix": 16}]),
+        ]
+    );
+}
+
+#[test]
+fn errors_are_records() {
+    let records = records("@");
+    assert_eq!(
+        records[0],
+        json!({"kind": "Error", "lexeme": "@", "line": 1, "column": 1, "start": 0, "end": 1, "message": "Unexpected character `@`"})
+    );
+    assert_eq!(records[1]["kind"], "Eof");
+}
+
+#[test]
+fn json_and_jsonl_hold_the_same_records() {
+    let source = "info \"multi\nline\";\n";
+    let (json, code) = dump("formats.az", source, &["--format=json"]);
+    assert_eq!(code, Some(0));
+    let (jsonl, _) = dump("formats.az", source, &["--format=jsonl"]);
+    let array: Value = serde_json::from_str(&json).unwrap();
+    let lines: Vec<Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(array, Value::Array(lines.clone()));
+    assert_eq!(lines[1]["value"], "multi\nline");
+    assert_eq!(lines.last().unwrap()["kind"], "Eof");
+}
+
+#[test]
+fn table() {
+    let (table, code) = dump("table.az", "info 'a';\n", &[]);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        table,
+        " LINE  COL  KIND               LEXEME
+    1    1  Keyword            info
+    1    6  Char               'a'
+    1    9  Semicolon          ;
+    2    1  Eof
+"
+    );
+}
+
+#[test]
+fn errors_exit_with_65() {
+    let (jsonl, code) = dump("error.az", "info @;", &["--format=jsonl", "--trivia"]);
+    assert_eq!(code, Some(65));
+    assert!(jsonl.contains(r#""kind":"Error""#));
+    let (_, code) = dump("error.az", "info;", &["--format=yaml"]);
+    assert_eq!(code, Some(64));
+}
@@ -0,0 +1,88 @@
+use std::cmp::Ordering;
+
+use azura::{
+    scanner::{BigIntLiteral, Scanner, TokenKind},
+    value::{OperationError, Value},
+};
+
+/// The value of the single integer literal in `source`
+fn literal(source: &str) -> Result<Value, OperationError> {
+    match Scanner::new(source).next().unwrap().unwrap().kind {
+        TokenKind::Integer(int) => Ok(Value::Int(int)),
+        TokenKind::BigInteger(literal) => Value::from_big_literal(literal),
+        other => panic!("expected an integer in {source:?}, got {other:?}"),
+    }
+}
+
+#[test]
+fn large_literals_never_overflow_in_the_scanner() {
+    let kinds: Vec<_> =
+        Scanner::new("9223372036854775807 9223372036854775808 0x1_0000_0000_0000_0000")
+            .map(|token| token.unwrap().kind)
+            .collect();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::Integer(isize::MAX),
+            TokenKind::BigInteger(BigIntLiteral {
+                digits: "9223372036854775808",
+                radix: 10
+            }),
+            TokenKind::BigInteger(BigIntLiteral {
+                digits: "1_0000_0000_0000_0000",
+                radix: 16
+            }),
+            TokenKind::Eof,
+        ]
+    );
+    assert_eq!(kinds[2].to_string(), "0x1_0000_0000_0000_0000");
+}
+
+#[test]
+fn numbers_mix() {
+    let int = Value::Int(3);
+    let float = Value::Float(0.5);
+    assert_eq!(int.add(&float), Ok(Value::Float(3.5)));
+    assert_eq!(int.div(&Value::Int(2)), Ok(Value::Int(1)));
+    assert_eq!(int.compare(&float), Ok(Some(Ordering::Greater)));
+    assert!(int.equals(&Value::Float(3.0)));
+    assert_eq!(
+        int.add(&Value::Nil),
+        Err(OperationError::InvalidOperands {
+            operation: "+",
+            left: "int",
+            right: "nil"
+        })
+    );
+}
+
+#[cfg(not(feature = "bigint"))]
+#[test]
+fn overflow_is_an_error() {
+    assert_eq!(
+        Value::Int(isize::MAX).add(&Value::Int(1)),
+        Err(OperationError::Overflow)
+    );
+    assert_eq!(
+        literal("9223372036854775808"),
+        Err(OperationError::Overflow)
+    );
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn overflow_promotes_to_bigint() {
+    let max = Value::Int(isize::MAX);
+    let big = max.add(&Value::Int(1)).unwrap();
+    assert_eq!(big.to_string(), "9223372036854775808");
+    assert_eq!(big.type_name(), "int");
+    assert_eq!(literal("9223372036854775808"), Ok(big.clone()));
+    assert_eq!(big.compare(&max), Ok(Some(Ordering::Greater)));
+    assert_eq!(big.compare(&Value::Float(1e19)), Ok(Some(Ordering::Less)));
+    // Results that fit are small integers again
+    assert_eq!(big.sub(&Value::Int(1)), Ok(max));
+    assert_eq!(
+        literal("0x1_0000_0000_0000_0000").unwrap().to_string(),
+        "18446744073709551616"
+    );
+}
@@ -5,8 +5,11 @@
 #include <stddef.h>
 #include <stdint.h>
 
+// Release builds with -DNDEBUG leave out the bytecode listing and the execution trace
+#ifndef NDEBUG
 #define DEBUG_PRINT_CODE
 #define DEBUG_TRACE_EXECUTION
+#endif
 
 #define UINT8_COUNT (UINT8_MAX + 1)
 